@@ -0,0 +1,74 @@
+use anyhow::Error;
+use log::{error, info};
+use regex::Regex;
+use std::path::Path;
+
+/// Replace the given time ranges (ms) of the audio file by a beep
+pub fn bleep_spans(audio_path: &String, spans: &Vec<(i32, i32)>) -> Result<(), Error> {
+    let ranges = spans.iter()
+        .map(|(start, end)| format!("between(t,{:.3},{:.3})", *start as f64 / 1000.0, *end as f64 / 1000.0))
+        .collect::<Vec<String>>()
+        .join("+");
+    // Mute the audio in the ranges and mix a 1kHz tone only audible in these ranges
+    let filter = format!(
+        "[0:a]volume=0:enable='{ranges}'[muted];sine=frequency=1000:sample_rate=44100,volume=0:enable='not({ranges})'[tone];[muted][tone]amix=inputs=2:duration=first,volume=2",
+        ranges = ranges
+    );
+    // Same extension as the source, so ffmpeg keeps its container and codec
+    let source = Path::new(audio_path);
+    let extension = source.extension()
+        .ok_or(anyhow::anyhow!("Unknown audio format - {}", audio_path))?
+        .to_string_lossy();
+    let output_path = source.with_extension(format!("redacted.{}", extension)).to_string_lossy().to_string();
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        audio_path.clone(),
+        "-filter_complex".to_string(),
+        filter,
+        "-map_metadata".to_string(),
+        "0".to_string(),
+    ];
+    if let Some((encoder, bitrate)) = source_codec(audio_path)? {
+        args.extend(["-c:a".to_string(), encoder]);
+        if let Some(bitrate) = bitrate {
+            args.extend(["-b:a".to_string(), bitrate]);
+        }
+    }
+    args.push(output_path.clone());
+    let ffmpegcommand = sidecar()?.args(args).output()?;
+    info!("[FFMPG] status: {:?}", ffmpegcommand.status);
+    if !ffmpegcommand.status.success() {
+        error!("[FFMPG] stderr: {:?}", String::from(&ffmpegcommand.stderr));
+        return Err(anyhow::anyhow!("FFMPEG failed to redact the audio"));
+    }
+    std::fs::rename(&output_path, audio_path)?;
+    info!("[FFMPG] COMPLETED - {} ranges redacted in {}", spans.len(), audio_path);
+    return Ok(());
+}
+
+
+fn sidecar() -> Result<tauri::api::process::Command, Error> {
+    return tauri::api::process::Command::new_sidecar("ffmpeg")
+        .map_err(|error| anyhow::anyhow!("Failed to create the `ffmpeg` command: {}", error));
+}
+
+/// Encoder and bitrate of the source audio stream, read from `ffmpeg -i`
+/// None lets ffmpeg pick the default encoder of the container
+fn source_codec(audio_path: &String) -> Result<Option<(String, Option<String>)>, Error> {
+    // Without an output ffmpeg exits with an error, but still prints the streams
+    let probe = sidecar()?.args(["-hide_banner", "-i", audio_path]).output()?;
+    let stream = Regex::new(r"Audio: (\w+)[^\n]*?(?:, (\d+) kb/s)?\r?\n").unwrap();
+    let captures = match stream.captures(&probe.stderr) {
+        Some(captures) => captures,
+        None => return Ok(None),
+    };
+    let encoder = match &captures[1] {
+        "mp3" => "libmp3lame",
+        "opus" => "libopus",
+        "vorbis" => "libvorbis",
+        codec => codec,
+    };
+    let bitrate = captures.get(2).map(|bitrate| format!("{}k", bitrate.as_str()));
+    return Ok(Some((encoder.to_string(), bitrate)));
+}
@@ -1,5 +1,6 @@
 pub mod recorder;
 pub mod cpal_audio;
 pub mod processor;
+pub mod bleep;
 #[cfg(target_os = "macos")]
 pub mod screen_capture_kit;
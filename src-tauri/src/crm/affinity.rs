@@ -4,10 +4,12 @@ use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE};
 use reqwest::StatusCode;
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use anyhow::Error;
-use log::{error, info};
+use log::{error, info, warn};
 use crate::ipc::ModelMutateResultData;
 use crate::model::{SettingController, Meeting};
+use crate::utils::markdown;
 use chrono::{Local, DateTime, NaiveDateTime, Utc};
 const ENDPOINT_BASE: &str = "https://api.affinity.co/";
 use serde_json::json;
@@ -306,6 +308,38 @@ pub fn get_organizations(
     }
 }
 
+/// Names of an organization & its persons, for the transcription vocabulary
+/// -> Every request has a timeout and the lookup stops at `deadline`, a slow CRM must not hold the transcription
+pub fn organization_names(
+    org_id: &str,
+    max_persons: usize,
+    timeout: Duration,
+    deadline: Duration,
+) -> Result<Vec<String>, Error> {
+    let client = Client::builder().timeout(timeout).build()?;
+    let api_key = get_api_key()?;
+    if org_id.is_empty() {
+        return Err(anyhow::Error::msg("organization id is needed"))
+    }
+    let started = Instant::now();
+    let organization = get(&client, &api_key, &format!("{}/organizations/{}", ENDPOINT_BASE, org_id))?;
+    let mut names = Vec::new();
+    if let Some(name) = organization["name"].as_str() {
+        names.push(name.to_string());
+    }
+    for person_id in organization["person_ids"].as_array().cloned().unwrap_or_default().iter().take(max_persons) {
+        if started.elapsed() >= deadline {
+            warn!("[Affinity] organization_names: deadline reached after {} names", names.len());
+            break;
+        }
+        let person = get(&client, &api_key, &format!("{}/persons/{}", ENDPOINT_BASE, person_id))?;
+        let first_name = person["first_name"].as_str().unwrap_or_default();
+        let last_name = person["last_name"].as_str().unwrap_or_default();
+        names.push(format!("{} {}", first_name, last_name));
+    }
+    return Ok(names);
+}
+
 pub fn _search_organizations(
     term: &str,
     page_size: Option<&str>,
@@ -337,6 +371,15 @@ pub fn _search_organizations(
     }
 }
 
+/// Organization with this exact name - None if there is no match
+pub fn find_organization_id(name: &str) -> Result<Option<String>, Error> {
+    let json = _search_organizations(name, Some("10"), None)?;
+    let organizations = json["organizations"].as_array().cloned().unwrap_or_default();
+    let organization = organizations.iter()
+        .find(|organization| organization["name"].as_str().map(|org_name| org_name.to_lowercase()) == Some(name.to_lowercase()));
+    return Ok(organization.and_then(|organization| organization["id"].as_i64()).map(|id| id.to_string()));
+}
+
 
 pub async fn async_search_organizations(
     term: &str,
@@ -397,12 +440,12 @@ pub fn create_note(
         }
     };
 
-    let mut note = format!("<p>{}<p><p>Generated with Watson</p><p>{}<p>{}", meeting.title, date, meeting.summary);
+    let mut note = format!("<p>{}</p><p>Generated with Watson</p><p>{}</p>{}", markdown::sanitize_html(&meeting.title), date, markdown::to_html(&meeting.summary));
     let mut data = HashMap::new();
 
     match meeting.publish_with_note {
         Some(true) => {
-            note = format!("{}<br><p><b>Personal note</b></p>{}", note, meeting.note);
+            note = format!("{}<br><p><b>Personal note</b></p>{}", note, markdown::sanitize_html(&meeting.note));
         }
         _ => (),
     }
@@ -0,0 +1,161 @@
+/*
+    * Periodic digest
+    * Summaries of the meetings of a period grouped by company, with their open action items
+    * Written as a dated Markdown report in the data folder, on demand or on a weekly schedule
+*/
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike};
+use log::{error, info};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+use crate::model::{DigestSchedule, Meeting};
+use crate::{MeetingControllerState, SettingControllerState};
+
+const SCHEDULER_INTERVAL_MINUTES: u64 = 15;
+const NO_COMPANY: &str = "Other meetings";
+
+#[derive(Clone, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct PeriodDigest {
+    pub from: String, // YYYY-MM-DD
+    pub to: String,
+    pub meeting_count: usize,
+    pub path: String,
+    pub content: String, // Markdown
+}
+
+/// Local date of a meeting
+pub fn meeting_date(meeting: &Meeting) -> Option<NaiveDate> {
+    return DateTime::parse_from_rfc3339(&meeting.datetime).ok()
+        .map(|datetime| datetime.with_timezone(&Local).date_naive());
+}
+
+pub fn file_name(from: &NaiveDate, to: &NaiveDate) -> String {
+    return format!("digest_{}_{}.md", from.format("%Y-%m-%d"), to.format("%Y-%m-%d"));
+}
+
+/// `meetings` must be ordered by date
+pub fn render(from: &NaiveDate, to: &NaiveDate, meetings: &Vec<Meeting>) -> String {
+    // Companies in order of their first meeting of the period
+    let mut companies: Vec<(String, Vec<&Meeting>)> = Vec::new();
+    for meeting in meetings.iter() {
+        let company = match meeting.company_name.trim().is_empty() {
+            true => NO_COMPANY.to_string(),
+            false => meeting.company_name.trim().to_string(),
+        };
+        match companies.iter_mut().find(|(name, _)| name == &company) {
+            Some((_, company_meetings)) => company_meetings.push(meeting),
+            None => companies.push((company, vec![meeting])),
+        }
+    }
+    // Meetings without company come last
+    companies.sort_by_key(|(name, _)| name == NO_COMPANY);
+
+    let mut report = format!("# Meetings digest - {} to {}\n\n", from.format("%Y-%m-%d"), to.format("%Y-%m-%d"));
+    report += &format!("{} meetings with {} companies.\n", meetings.len(), companies.iter().filter(|(name, _)| name != NO_COMPANY).count());
+    for (company, company_meetings) in companies.iter() {
+        report += &format!("\n## {}\n", company);
+        let mut open_items = Vec::new();
+        for meeting in company_meetings.iter() {
+            let date = meeting_date(meeting).map(|date| date.format("%Y-%m-%d").to_string()).unwrap_or(meeting.datetime.clone());
+            let summary = match meeting.summary.trim().is_empty() {
+                true => "_No summary_".to_string(),
+                false => meeting.summary.trim().to_string(),
+            };
+            report += &format!("\n### {} - {}\n\n{}\n", date, meeting.title, summary);
+            for item in meeting.action_items.clone().unwrap_or_default().into_iter().filter(|item| !item.done) {
+                open_items.push(item);
+            }
+        }
+        if !open_items.is_empty() {
+            report += "\n**Open action items**\n\n";
+            for item in open_items.iter() {
+                let details: Vec<String> = [item.owner.clone(), item.due_date.clone().map(|due_date| format!("due {}", due_date))]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                match details.is_empty() {
+                    true => report += &format!("- [ ] {}\n", item.task),
+                    false => report += &format!("- [ ] {} ({})\n", item.task, details.join(", ")),
+                }
+            }
+        }
+    }
+    return report;
+}
+
+/// Last scheduled run before `now` -> Period of the report due at that time
+/// The period ends the day before the run, so meetings later on the run day go in the next report
+fn due_period(schedule: &DigestSchedule, now: &DateTime<Local>) -> (NaiveDate, NaiveDate) {
+    let today = now.date_naive();
+    let days_since = (today.weekday().num_days_from_monday() + 7 - schedule.weekday as u32) % 7;
+    let mut run_date = today - chrono::Duration::days(days_since as i64);
+    if days_since == 0 && now.hour() < schedule.hour as u32 {
+        run_date = run_date - chrono::Duration::days(7);
+    }
+    let to = run_date - chrono::Duration::days(1);
+    let from = to - chrono::Duration::days(schedule.period_days.max(1) as i64 - 1);
+    return (from, to);
+}
+
+/// Check the schedule regularly - A report missed while the app was closed is written at the next start
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let schedule = {
+                let setting_controller = app.state::<SettingControllerState>();
+                let setting_controller = setting_controller.0.lock().unwrap();
+                setting_controller.get_setting().ok().and_then(|setting| setting.digest_schedule)
+            };
+            if let Some((from, to)) = schedule.filter(|schedule| schedule.enabled).map(|schedule| due_period(&schedule, &Local::now())) {
+                let meeting_controller = app.state::<MeetingControllerState>();
+                let meeting_controller = meeting_controller.0.lock().unwrap();
+                if !meeting_controller.digest_exists(&from, &to) {
+                    info!("[Digest] Scheduled digest {} to {}", from, to);
+                    match meeting_controller.period_digest(from, to) {
+                        Ok(digest) => { let _ = app.emit_all("DIGEST_READY", digest); },
+                        Err(error) => error!("[Digest] Scheduled digest failed: {:?}", error),
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(SCHEDULER_INTERVAL_MINUTES * 60)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn weekly_on_monday() -> DigestSchedule {
+        return DigestSchedule { enabled: true, weekday: 0, hour: 8, period_days: 7 };
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    }
+
+    #[test]
+    fn ends_the_day_before_the_run() {
+        // Wednesday -> Last run on Monday the 11th
+        let now = Local.with_ymd_and_hms(2024, 3, 13, 10, 0, 0).unwrap();
+        assert_eq!(due_period(&weekly_on_monday(), &now), (date(2024, 3, 4), date(2024, 3, 10)));
+    }
+
+    #[test]
+    fn waits_for_the_hour_on_the_run_day() {
+        let before = Local.with_ymd_and_hms(2024, 3, 11, 7, 0, 0).unwrap();
+        assert_eq!(due_period(&weekly_on_monday(), &before), (date(2024, 2, 26), date(2024, 3, 3)));
+        let after = Local.with_ymd_and_hms(2024, 3, 11, 9, 0, 0).unwrap();
+        assert_eq!(due_period(&weekly_on_monday(), &after), (date(2024, 3, 4), date(2024, 3, 10)));
+    }
+
+    #[test]
+    fn periods_follow_each_other() {
+        let first = due_period(&weekly_on_monday(), &Local.with_ymd_and_hms(2024, 3, 11, 9, 0, 0).unwrap());
+        let next = due_period(&weekly_on_monday(), &Local.with_ymd_and_hms(2024, 3, 18, 9, 0, 0).unwrap());
+        assert_eq!(next.0, first.1 + chrono::Duration::days(1));
+    }
+}
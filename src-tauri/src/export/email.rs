@@ -0,0 +1,66 @@
+/*
+    * Follow-up email export
+    * `.eml` draft opened by the mail client, or a `mailto:` link
+*/
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use crate::model::FollowUpDraft;
+
+#[derive(Clone, Debug, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum FollowUpFormat {
+    Eml,
+    Mailto,
+}
+
+/// RFC 3986 unreserved characters are kept, everything else is percent-encoded
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    return encoded;
+}
+
+/// RFC 2047 - Headers are ASCII only
+fn encode_header(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_string();
+    }
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => encoded.push(byte as char),
+            b' ' => encoded.push('_'),
+            _ => encoded.push_str(&format!("={:02X}", byte)),
+        }
+    }
+    return format!("=?UTF-8?Q?{}?=", encoded);
+}
+
+pub fn to_mailto(draft: &FollowUpDraft) -> String {
+    // Line breaks must be CRLF in a mailto body
+    let body = draft.body.replace("\r\n", "\n").replace('\n', "\r\n");
+    return format!("mailto:?subject={}&body={}", percent_encode(&draft.subject), percent_encode(&body));
+}
+
+/// `X-Unsent` makes Outlook & Apple Mail open the file as a draft to send
+pub fn to_eml(draft: &FollowUpDraft) -> String {
+    let body = draft.body.replace("\r\n", "\n").replace('\n', "\r\n");
+    return format!(
+        "X-Unsent: 1\r\nSubject: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n{}\r\n",
+        encode_header(&draft.subject), body
+    );
+}
+
+pub fn render(draft: &Option<FollowUpDraft>, format: &FollowUpFormat) -> Result<String, Error> {
+    let draft = draft.as_ref().ok_or(anyhow::anyhow!("No follow-up email drafted for this meeting"))?;
+    match format {
+        FollowUpFormat::Eml => return Ok(to_eml(draft)),
+        FollowUpFormat::Mailto => return Ok(to_mailto(draft)),
+    }
+}
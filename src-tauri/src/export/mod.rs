@@ -0,0 +1,3 @@
+pub mod digest;
+pub mod email;
+pub mod transcript;
\ No newline at end of file
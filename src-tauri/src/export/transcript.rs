@@ -0,0 +1,114 @@
+/*
+    * Transcript export
+    * SRT & WebVTT subtitles, plain text with timestamps and JSON
+*/
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use crate::model::{Meeting, TranscriptSegment};
+
+#[derive(Clone, Debug, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum TranscriptFormat {
+    Srt,
+    WebVtt,
+    Text,
+    Json,
+}
+
+impl TranscriptFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Srt => "srt",
+            TranscriptFormat::WebVtt => "vtt",
+            TranscriptFormat::Text => "txt",
+            TranscriptFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TranscriptJson<'a> {
+    uuid: String,
+    title: &'a String,
+    datetime: &'a String,
+    language: &'a Option<String>,
+    transcript: &'a String,
+    segments: &'a Vec<TranscriptSegment>,
+}
+
+pub fn render(meeting: &Meeting, format: &TranscriptFormat) -> Result<String, Error> {
+    let empty = Vec::new();
+    let segments = meeting.segments.as_ref().unwrap_or(&empty);
+    match format {
+        TranscriptFormat::Json => {
+            let json = TranscriptJson {
+                uuid: meeting.get_uuid(),
+                title: &meeting.title,
+                datetime: &meeting.datetime,
+                language: &meeting.detected_language,
+                transcript: &meeting.transcript,
+                segments,
+            };
+            return Ok(serde_json::to_string_pretty(&json)?);
+        },
+        // Old meetings don't have timestamps, the transcript is still worth exporting
+        TranscriptFormat::Text if segments.is_empty() => return Ok(meeting.transcript.clone()),
+        _ if segments.is_empty() => {
+            return Err(anyhow::anyhow!("No timed transcript available for this meeting - Transcribe it again to export subtitles"));
+        },
+        TranscriptFormat::Srt => return Ok(to_srt(segments)),
+        TranscriptFormat::WebVtt => return Ok(to_webvtt(segments)),
+        TranscriptFormat::Text => return Ok(to_text(segments)),
+    }
+}
+
+fn with_speaker(segment: &TranscriptSegment) -> String {
+    match &segment.speaker {
+        Some(speaker) => format!("Speaker {}: {}", speaker, segment.text),
+        None => segment.text.clone(),
+    }
+}
+
+/// hh:mm:ss{separator}mmm
+/// HH:MM:SS - Hours take more digits past 99
+fn clock(ms: i32) -> String {
+    let ms = ms.max(0);
+    let hours = ms / 3_600_000;
+    let minutes = (ms % 3_600_000) / 60_000;
+    let seconds = (ms % 60_000) / 1_000;
+    return format!("{:02}:{:02}:{:02}", hours, minutes, seconds);
+}
+
+fn timestamp(ms: i32, separator: char) -> String {
+    return format!("{}{}{:03}", clock(ms), separator, ms.max(0) % 1_000);
+}
+
+fn to_srt(segments: &Vec<TranscriptSegment>) -> String {
+    let mut srt = String::new();
+    for (index, segment) in segments.iter().enumerate() {
+        srt += &format!("{}\n{} --> {}\n{}\n\n", index + 1, timestamp(segment.start, ','), timestamp(segment.end, ','), with_speaker(segment));
+    }
+    return srt;
+}
+
+fn to_webvtt(segments: &Vec<TranscriptSegment>) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+    for segment in segments {
+        let text = match &segment.speaker {
+            Some(speaker) => format!("<v Speaker {}>{}", speaker, segment.text),
+            None => segment.text.clone(),
+        };
+        vtt += &format!("{} --> {}\n{}\n\n", timestamp(segment.start, '.'), timestamp(segment.end, '.'), text);
+    }
+    return vtt;
+}
+
+fn to_text(segments: &Vec<TranscriptSegment>) -> String {
+    let mut text = String::new();
+    for segment in segments {
+        // Milliseconds are noise in a text transcript
+        text += &format!("[{}] {}\n", clock(segment.start), with_speaker(segment));
+    }
+    return text;
+}
@@ -0,0 +1,29 @@
+use super::{CompanyDigestParams, IpcResponse};
+use crate::MeetingControllerState;
+use crate::model::CompanyDigest;
+use crate::summarizer::company;
+use log::info;
+use tauri::command;
+
+
+#[command(async)]
+pub fn summarize_company(
+    params: CompanyDigestParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<CompanyDigest> {
+    // The digest is generated again only if a meeting of the company changed
+    info!("summarize_company called");
+    let meetings = match meeting_controller_state.0.lock().unwrap().company_meetings(&params.company_id) {
+        Ok(meetings) => meetings,
+        Err(error) => return IpcResponse::from(Err(error)),
+    };
+    let cached = match params.force.unwrap_or(false) {
+        true => None,
+        false => CompanyDigest::load_valid(&params.company_id, &meetings),
+    };
+    let result = match cached {
+        Some(digest) => Ok(digest),
+        None => company::digest_company(&params.company_id, &meetings, params.force.unwrap_or(false)).and_then(|digest| digest.save().map(|_| digest)),
+    };
+    return IpcResponse::from(result);
+}
@@ -0,0 +1,70 @@
+use super::{AskMeetingParams, GetParams, IpcResponse, ModelMutateResultData};
+use crate::MeetingControllerState;
+use crate::model::{Conversation, ConversationMessage, ConversationRole};
+use crate::summarizer::chat;
+use log::info;
+use tauri::command;
+
+
+#[command(async)]
+pub fn ask_meeting(
+    params: AskMeetingParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<ConversationMessage> {
+    // Answer without blocking the UI
+    info!("ask_meeting called");
+    if params.question.trim().is_empty() {
+        return IpcResponse::from(Err(anyhow::anyhow!("The question is empty")));
+    }
+    let meeting = {
+        let meeting_controller = meeting_controller_state.0.lock().unwrap();
+        match meeting_controller.get(params.id.clone()) {
+            Ok(meeting) => meeting,
+            Err(error) => return IpcResponse::from(Err(error)),
+        }
+    };
+    let mut conversation = match Conversation::load(params.id.clone()) {
+        Ok(conversation) => conversation,
+        Err(error) => return IpcResponse::from(Err(error)),
+    };
+    let answer = match chat::ask(&meeting, &conversation.messages, &params.question) {
+        Ok(answer) => answer,
+        Err(error) => return IpcResponse::from(Err(error)),
+    };
+    conversation.messages.push(ConversationMessage::new(ConversationRole::User, params.question, Vec::new()));
+    conversation.messages.push(answer.clone());
+    return IpcResponse::from(conversation.save().map(|_| answer));
+}
+
+/// Conversations are only read & written for known meetings
+fn check_meeting(meeting_controller_state: &tauri::State<MeetingControllerState>, id: &String) -> Result<(), anyhow::Error> {
+    if !meeting_controller_state.0.lock().unwrap().meetings.contains_key(id) {
+        return Err(anyhow::anyhow!("Meeting does not exist"));
+    }
+    return Ok(());
+}
+
+#[command]
+pub fn get_meeting_conversation(
+    params: GetParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<Conversation> {
+    info!("get_meeting_conversation called");
+    if let Err(error) = check_meeting(&meeting_controller_state, &params.id) {
+        return IpcResponse::from(Err(error));
+    }
+    return IpcResponse::from(Conversation::load(params.id));
+}
+
+#[command]
+pub fn clear_meeting_conversation(
+    params: GetParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<ModelMutateResultData> {
+    info!("clear_meeting_conversation called");
+    if let Err(error) = check_meeting(&meeting_controller_state, &params.id) {
+        return IpcResponse::from(Err(error));
+    }
+    let conversation = Conversation { meeting_id: params.id.clone(), messages: Vec::new() };
+    return IpcResponse::from(conversation.save().map(|_| ModelMutateResultData { id: params.id }));
+}
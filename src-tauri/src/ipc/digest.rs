@@ -0,0 +1,20 @@
+use super::{DigestParams, IpcResponse};
+use crate::MeetingControllerState;
+use crate::export::digest::PeriodDigest;
+use chrono::NaiveDate;
+use log::info;
+use tauri::command;
+
+
+#[command]
+pub fn generate_digest(
+    params: DigestParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<PeriodDigest> {
+    info!("generate_digest called");
+    let period = NaiveDate::parse_from_str(&params.from, "%Y-%m-%d")
+        .and_then(|from| NaiveDate::parse_from_str(&params.to, "%Y-%m-%d").map(|to| (from, to)))
+        .map_err(|error| anyhow::anyhow!("Dates must be YYYY-MM-DD: {}", error));
+    let meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(period.and_then(|(from, to)| meeting_controller.period_digest(from, to)));
+}
@@ -4,11 +4,20 @@
     Async logic is handled are
     Sync logic is handled in the model controller
 */
-use super::{DeleteParams, GetParams, IpcResponse, UpdateParams, ModelMutateResultData };
-use crate::MeetingControllerState;
-use crate::model::{Meeting, MeetingForUpdate, MeetingsRef};
-use log::info;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use super::{DeleteParams, GetParams, IpcResponse, UpdateParams, ModelMutateResultData, ExportTranscriptParams, ExportFollowUpParams, SummarizeParams, ActionItemDoneParams, NoteRevisionParams, SummaryDiffParams, SummaryVersionParams };
+use crate::{MeetingControllerState, SearchIndexState, SettingControllerState, SummaryStreamState};
+use crate::model::{ActionItem, FollowUpDraft, Meeting, MeetingForUpdate, MeetingsRef, OpenActionItem, SummaryVersion};
+use crate::utils::diff::DiffLine;
+use crate::summarizer::{action_items, follow_up};
+use crate::summarizer::metadata::{self, MeetingMetadata};
+use crate::summarizer::template::PromptPreview;
+use crate::summarizer::{SummaryObserver, SummaryProgress, SummaryToken};
+use crate::transcriber::CancelFlag;
+use log::{error, info};
 use tauri::command;
+use uuid::Uuid;
 
 #[command]
 pub fn get_meeting(
@@ -43,30 +52,39 @@ pub fn update_meeting(
 #[command]
 pub fn delete_meeting(
     params: DeleteParams, 
-    meeting_controller_state: tauri::State<MeetingControllerState>
+    meeting_controller_state: tauri::State<MeetingControllerState>,
+    search_index_state: tauri::State<SearchIndexState>
 ) -> IpcResponse<ModelMutateResultData> {
     info!("delete_meeting called");
     let mut meeting_controller = meeting_controller_state.0.lock().unwrap();
-    return IpcResponse::from(meeting_controller.delete(params.id));
+    let result = meeting_controller.delete(params.id.clone())
+        .and_then(|data| search_index_state.0.lock().unwrap().remove(&params.id).map(|_| data));
+    return IpcResponse::from(result);
 }
 
 #[command]
 pub fn delete_all_meeting(
-    meeting_controller_state: tauri::State<MeetingControllerState>
+    meeting_controller_state: tauri::State<MeetingControllerState>,
+    search_index_state: tauri::State<SearchIndexState>
 ) -> IpcResponse<()> {
     info!("delete_all_meetings called");
     let mut meeting_controller = meeting_controller_state.0.lock().unwrap();
-    return IpcResponse::from(meeting_controller.delete_all());
+    let result = meeting_controller.delete_all()
+        .and_then(|_| search_index_state.0.lock().unwrap().clear());
+    return IpcResponse::from(result);
 }
 
 #[command]
 pub fn archive_meeting(
     params: DeleteParams, 
-    meeting_controller_state: tauri::State<MeetingControllerState>
+    meeting_controller_state: tauri::State<MeetingControllerState>,
+    search_index_state: tauri::State<SearchIndexState>
 ) -> IpcResponse<ModelMutateResultData> {
     info!("archive_meeting called");
     let mut meeting_controller = meeting_controller_state.0.lock().unwrap();
-    return IpcResponse::from(meeting_controller.delete(params.id));
+    let result = meeting_controller.delete(params.id.clone())
+        .and_then(|data| search_index_state.0.lock().unwrap().remove(&params.id).map(|_| data));
+    return IpcResponse::from(result);
 }
 
 #[command]
@@ -78,6 +96,16 @@ pub fn export_all_meeting(
     return IpcResponse::from(meeting_controller.export_all());
 } 
 
+#[command]
+pub fn export_transcript_meeting(
+    params: ExportTranscriptParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<String> {
+    info!("export_transcript_meeting called");
+    let meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.export_transcript(params.id, params.format));
+}
+
 #[command]
 pub fn increment_async_ops_meeting(
     params: GetParams, 
@@ -98,20 +126,63 @@ pub fn decrement_async_ops_meeting(
     return IpcResponse::from(meeting_controller.decrement_async_ops(params.id));
 }
 
+/// Forward the progress & the generated tokens to the window, register the stream so it can be cancelled
+fn summary_observer(window: &tauri::Window, stream_state: &SummaryStreamState, meeting_id: &String, stream_id: &String) -> SummaryObserver {
+    let cancel: CancelFlag = Arc::new(AtomicBool::new(false));
+    stream_state.0.lock().unwrap().insert(stream_id.clone(), cancel.clone());
+    let (progress_window, progress_meeting_id, progress_stream_id) = (window.clone(), meeting_id.clone(), stream_id.clone());
+    let (token_window, token_meeting_id, token_stream_id) = (window.clone(), meeting_id.clone(), stream_id.clone());
+    return SummaryObserver {
+        progress: Arc::new(move |stage, step, total_steps| {
+            let progress = SummaryProgress { stream_id: progress_stream_id.clone(), meeting_id: progress_meeting_id.clone(), stage, step, total_steps };
+            if let Err(error) = progress_window.emit("SUMMARY_PROGRESS", progress) {
+                error!("Error while emitting summary progress: {:?}", error);
+            }
+        }),
+        token: Arc::new(move |token| {
+            let token = SummaryToken { stream_id: token_stream_id.clone(), meeting_id: token_meeting_id.clone(), token: token.to_string() };
+            if let Err(error) = token_window.emit("SUMMARY_TOKEN", token) {
+                error!("Error while emitting summary token: {:?}", error);
+            }
+        }),
+        cancel,
+    };
+}
+
+#[command]
+pub fn cancel_summary_stream(
+    params: GetParams,
+    stream_state: tauri::State<SummaryStreamState>
+) -> IpcResponse<ModelMutateResultData> {
+    info!("cancel_summary_stream called");
+    let streams = stream_state.0.lock().unwrap();
+    let result = match streams.get(&params.id) {
+        // The generation stop at the next token received
+        Some(cancel) => {
+            cancel.store(true, Ordering::SeqCst);
+            Ok(ModelMutateResultData { id: params.id })
+        },
+        None => Err(anyhow::anyhow!("Summary stream is not running")),
+    };
+    return IpcResponse::from(result);
+}
+
 #[command]
 pub fn summarize_meeting(
-    params: GetParams, 
+    params: SummarizeParams, 
     meeting_controller_state: tauri::State<MeetingControllerState>
 ) -> IpcResponse<ModelMutateResultData> {
     info!("summarize_meeting called");
     let mut meeting_controller = meeting_controller_state.0.lock().unwrap();
-    return IpcResponse::from(meeting_controller.summarize_meeting(params.id));
+    return IpcResponse::from(meeting_controller.summarize_meeting(params.id, params.force.unwrap_or(false)));
 }
 
 #[command(async)]
 pub fn async_summarize_meeting(
-    params: GetParams, 
-    meeting_controller_state: tauri::State<'_, MeetingControllerState>
+    params: SummarizeParams, 
+    meeting_controller_state: tauri::State<'_, MeetingControllerState>,
+    stream_state: tauri::State<'_, SummaryStreamState>,
+    window: tauri::Window
 ) -> IpcResponse<ModelMutateResultData> {
     //  Summarize without blocking the UI
     info!("summarize_meeting called");
@@ -130,10 +201,14 @@ pub fn async_summarize_meeting(
         let _ = meeting_controller.increment_async_ops(params.id.clone());
         // Releasing lock on meeting_controller_state"
     }
-    // ? Summarizing can take a while
-    let summary =  match meeting.summarize() {
+    // ? Summarizing can take a while - The answer is streamed to the window
+    let stream_id = params.stream_id.clone().unwrap_or(Uuid::new_v4().to_string());
+    let observer = summary_observer(&window, &stream_state, &params.id, &stream_id);
+    let result = meeting.summarize(&observer, params.force.unwrap_or(false));
+    stream_state.0.lock().unwrap().remove(&stream_id);
+    let (summary, summary_info) =  match result {
         Ok(_) => {
-            meeting.summary
+            (meeting.summary, meeting.summary_info)
         },
         Err(error) => {
             let mut meeting_controller: std::sync::MutexGuard<'_, crate::model::MeetingController> = meeting_controller_state.0.lock().unwrap();
@@ -152,24 +227,47 @@ pub fn async_summarize_meeting(
         }
     };
     meeting.summary = summary;
+    meeting.summary_info = summary_info;
     let _ = meeting_controller.decrement_async_ops(params.id.clone());
     return IpcResponse::from(meeting_controller.update(meeting));
 }
 
+#[command]
+pub fn preview_prompt(
+    params: GetParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<PromptPreview> {
+    info!("preview_prompt called");
+    let meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.preview_prompt(params.id));
+}
+
+#[command]
+pub fn render_summary_html(
+    params: GetParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<String> {
+    info!("render_summary_html called");
+    let meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.render_summary_html(params.id));
+}
+
 #[command]
 pub fn improve_note_meeting(
-    params: GetParams, 
+    params: SummarizeParams, 
     meeting_controller_state: tauri::State<MeetingControllerState>
 ) -> IpcResponse<ModelMutateResultData> {
     info!("improve_note_meeting called");
     let mut meeting_controller = meeting_controller_state.0.lock().unwrap(); 
-    return IpcResponse::from(meeting_controller.improve_note_meeting(params.id));
+    return IpcResponse::from(meeting_controller.improve_note_meeting(params.id, params.force.unwrap_or(false)));
 }
 
 #[command(async)]
 pub fn async_improve_note_meeting(
-    params: GetParams, 
-    meeting_controller_state: tauri::State<MeetingControllerState>
+    params: SummarizeParams, 
+    meeting_controller_state: tauri::State<'_, MeetingControllerState>,
+    stream_state: tauri::State<'_, SummaryStreamState>,
+    window: tauri::Window
 ) -> IpcResponse<ModelMutateResultData> {
     // Improve note without blocking the UI
     info!("improve_note_meeting called");
@@ -188,10 +286,14 @@ pub fn async_improve_note_meeting(
         let _ = meeting_controller.increment_async_ops(params.id.clone());
         // Releasing lock on meeting_controller_state"
     }
-    // ? Summarizing can take a while
-    let note =  match meeting.improve_note() {
+    // ? Summarizing can take a while - The answer is streamed to the window
+    let stream_id = params.stream_id.clone().unwrap_or(Uuid::new_v4().to_string());
+    let observer = summary_observer(&window, &stream_state, &params.id, &stream_id);
+    let result = meeting.improve_note(&observer, params.force.unwrap_or(false));
+    stream_state.0.lock().unwrap().remove(&stream_id);
+    let note_revision = match result {
         Ok(_) => {
-            meeting.note
+            meeting.note_revision
         },
         Err(error) => {
             let mut meeting_controller: std::sync::MutexGuard<'_, crate::model::MeetingController> = meeting_controller_state.0.lock().unwrap();
@@ -202,16 +304,190 @@ pub fn async_improve_note_meeting(
 
     // Acquiring lock on meeting_controller_state"
     let mut meeting_controller: std::sync::MutexGuard<'_, crate::model::MeetingController> = meeting_controller_state.0.lock().unwrap();
+    let _ = meeting_controller.decrement_async_ops(params.id.clone());
+    return IpcResponse::from(meeting_controller.set_note_revision(params.id, note_revision));
+}
+
+#[command]
+pub fn accept_note_revision(
+    params: NoteRevisionParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<ModelMutateResultData> {
+    info!("accept_note_revision called");
+    let mut meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.accept_note_revision(params.id, params.lines));
+}
+
+#[command]
+pub fn reject_note_revision(
+    params: GetParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<ModelMutateResultData> {
+    info!("reject_note_revision called");
+    let mut meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.reject_note_revision(params.id));
+}
+
+#[command(async)]
+pub fn async_generate_metadata_meeting(
+    params: GetParams,
+    app: tauri::AppHandle
+) -> IpcResponse<MeetingMetadata> {
+    // Generate title, company, participants & prompt without blocking the UI
+    info!("generate_metadata_meeting called");
+    return IpcResponse::from(metadata::generate_and_apply(&app, params.id));
+}
+
+#[command(async)]
+pub fn draft_follow_up(
+    params: GetParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>,
+    setting_controller_state: tauri::State<SettingControllerState>
+) -> IpcResponse<FollowUpDraft> {
+    // Draft the email without blocking the UI
+    info!("draft_follow_up called");
+    let template = setting_controller_state.0.lock().unwrap().get_setting().ok().and_then(|setting| setting.follow_up_prompt);
+    let meeting;
+    {
+        // Acquiring lock on meeting_controller_state"
+        let mut meeting_controller: std::sync::MutexGuard<'_, crate::model::MeetingController> = meeting_controller_state.0.lock().unwrap();
+        meeting = match meeting_controller.get(params.id.clone()) {
+            Ok(m) => {
+                m
+            },
+            Err(error) => {
+                return IpcResponse::from(Err(error));
+            }
+        };
+        let _ = meeting_controller.increment_async_ops(params.id.clone());
+        // Releasing lock on meeting_controller_state"
+    }
+    let draft = match follow_up::draft_follow_up(&meeting, &template) {
+        Ok(draft) => draft,
+        Err(error) => {
+            let mut meeting_controller: std::sync::MutexGuard<'_, crate::model::MeetingController> = meeting_controller_state.0.lock().unwrap();
+            let _ = meeting_controller.decrement_async_ops(params.id.clone());
+            return IpcResponse::from(Err(error));
+        }
+    };
+
+    let mut meeting_controller: std::sync::MutexGuard<'_, crate::model::MeetingController> = meeting_controller_state.0.lock().unwrap();
+    let mut meeting = match meeting_controller.get(params.id.clone()) {
+        Ok(m) => {
+            m
+        },
+        Err(error) => {
+            let _ = meeting_controller.decrement_async_ops(params.id.clone());
+            return IpcResponse::from(Err(error));
+        }
+    };
+    meeting.follow_up = Some(draft.clone());
+    let _ = meeting_controller.decrement_async_ops(params.id.clone());
+    return IpcResponse::from(meeting_controller.update(meeting).map(|_| draft));
+}
+
+#[command]
+pub fn export_follow_up(
+    params: ExportFollowUpParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<String> {
+    info!("export_follow_up called");
+    let meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.export_follow_up(params.id, params.format));
+}
+
+#[command(async)]
+pub fn async_extract_action_items_meeting(
+    params: GetParams, 
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<ModelMutateResultData> {
+    // Extract action items without blocking the UI
+    info!("extract_action_items_meeting called");
+    let meeting;
+    {
+        // Acquiring lock on meeting_controller_state"
+        let mut meeting_controller: std::sync::MutexGuard<'_, crate::model::MeetingController> = meeting_controller_state.0.lock().unwrap();
+        meeting = match meeting_controller.get(params.id.clone()) {
+            Ok(m) => {
+                m
+            },
+            Err(error) => {
+                return IpcResponse::from(Err(error));
+            }
+        };
+        let _ = meeting_controller.increment_async_ops(params.id.clone());
+        // Releasing lock on meeting_controller_state"
+    }
+    let extracted = match action_items::extract_action_items(&params.id, &meeting.transcript, &meeting.note, &meeting.datetime) {
+        Ok(extracted) => extracted,
+        Err(error) => {
+            let mut meeting_controller: std::sync::MutexGuard<'_, crate::model::MeetingController> = meeting_controller_state.0.lock().unwrap();
+            let _ = meeting_controller.decrement_async_ops(params.id.clone());
+            return IpcResponse::from(Err(error));
+        }
+    };
+
+    // Items could have been marked as done in the meantime
+    let mut meeting_controller: std::sync::MutexGuard<'_, crate::model::MeetingController> = meeting_controller_state.0.lock().unwrap();
     let mut meeting = match meeting_controller.get(params.id.clone()) {
         Ok(m) => {
             m
         },
         Err(error) => {
-           let _ = meeting_controller.decrement_async_ops(params.id.clone());
+            let _ = meeting_controller.decrement_async_ops(params.id.clone());
             return IpcResponse::from(Err(error));
         }
     };
-    meeting.summary = note;
+    meeting.set_extracted_action_items(extracted);
     let _ = meeting_controller.decrement_async_ops(params.id.clone());
     return IpcResponse::from(meeting_controller.update(meeting));
 }
+
+#[command]
+pub fn set_action_item_done(
+    params: ActionItemDoneParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<ActionItem> {
+    info!("set_action_item_done called");
+    let mut meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.set_action_item_done(params.meeting_id, params.item_id, params.done));
+}
+
+#[command]
+pub fn list_open_action_items(
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<Vec<OpenActionItem>> {
+    info!("list_open_action_items called");
+    let meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.list_open_action_items());
+}
+
+#[command]
+pub fn list_summary_versions(
+    params: GetParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<Vec<SummaryVersion>> {
+    info!("list_summary_versions called");
+    let meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.list_summary_versions(params.id));
+}
+
+#[command]
+pub fn diff_summary_versions(
+    params: SummaryDiffParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<Vec<DiffLine>> {
+    info!("diff_summary_versions called");
+    let meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.diff_summary_versions(params.id, params.from, params.to));
+}
+
+#[command]
+pub fn restore_summary_version(
+    params: SummaryVersionParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>
+) -> IpcResponse<ModelMutateResultData> {
+    info!("restore_summary_version called");
+    let mut meeting_controller = meeting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(meeting_controller.restore_summary_version(params.id, params.version_id));
+}
@@ -12,21 +12,33 @@
 //*   - This module re-exports the appropriate sub-module constructs as their hierarchy is irrelevant to callers.
 
 mod params;
+mod company;
+mod conversation;
+mod digest;
 mod meeting;
 mod response;
 mod recording;
 mod setting;
 mod crm;
 mod session;
+mod search;
+mod transcription;
+mod usage;
 
 // --- re-exports
 pub use params::*;
+pub use company::*;
+pub use conversation::*;
+pub use digest::*;
 pub use meeting::*;
 pub use response::*;
 pub use recording::*;
 pub use setting::*;
 pub use crm::*;
 pub use session::*;
+pub use search::*;
+pub use transcription::*;
+pub use usage::*;
 
 use ts_rs::TS;
 use serde::Serialize;
@@ -7,6 +7,9 @@
 use serde::Deserialize;
 
 use crate::audio::cpal_audio::cpal_utils;
+use crate::export::email::FollowUpFormat;
+use crate::export::transcript::TranscriptFormat;
+use crate::model::PromptPackFormat;
 
 #[derive(Deserialize)]
 pub struct CreateParams<D> {
@@ -24,10 +27,91 @@ pub struct GetParams {
 	pub id: String,
 }
 
+#[derive(Deserialize)]
+pub struct SummarizeParams {
+	pub id: String,
+	pub stream_id: Option<String>, // Generated if not given, sent with each token
+	pub force: Option<bool>, // Ignore the cached answer
+}
+
+#[derive(Deserialize)]
+pub struct ActionItemDoneParams {
+	pub meeting_id: String,
+	pub item_id: String,
+	pub done: bool,
+}
+
+#[derive(Deserialize)]
+pub struct AskMeetingParams {
+	pub id: String,
+	pub question: String,
+}
+
+#[derive(Deserialize)]
+pub struct SemanticSearchParams {
+	pub query: String,
+	pub limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct SummaryVersionParams {
+	pub id: String,
+	pub version_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct CompanyDigestParams {
+	pub company_id: String,
+	pub force: Option<bool>, // Ignore the cached digest
+}
+
+#[derive(Deserialize)]
+pub struct DigestParams {
+	pub from: String, // YYYY-MM-DD
+	pub to: String,
+}
+
+#[derive(Deserialize)]
+pub struct ExportPromptPackParams {
+	pub format: PromptPackFormat,
+	pub names: Option<Vec<String>>, // Every prompt if not set
+}
+
+#[derive(Deserialize)]
+pub struct ImportPromptPackParams {
+	pub path: String, // `.yaml` or `.yml` for YAML, JSON otherwise
+}
+
+#[derive(Deserialize)]
+pub struct ExportFollowUpParams {
+	pub id: String,
+	pub format: FollowUpFormat,
+}
+
+#[derive(Deserialize)]
+pub struct NoteRevisionParams {
+	pub id: String,
+	pub lines: Option<Vec<usize>>, // Indexes of the diff lines to apply - Every change if not set
+}
+
+#[derive(Deserialize)]
+pub struct SummaryDiffParams {
+	pub id: String,
+	pub from: Option<String>, // Version id - Current summary if not set
+	pub to: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UsageReportParams {
+	pub from: Option<String>, // RFC 3339 - Start of the month if not set
+	pub to: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct GetTranscriptParams {
 	pub path: String,
-	pub language: String
+	pub language: String,
+	pub meeting_id: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -44,3 +128,9 @@ pub struct QueryParams {
 pub struct GetRecordingStartParams {
 	pub recording_devices: cpal_utils::RecordingDevices,
 }
+
+#[derive(Deserialize)]
+pub struct ExportTranscriptParams {
+	pub id: String,
+	pub format: TranscriptFormat,
+}
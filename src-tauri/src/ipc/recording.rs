@@ -1,11 +1,11 @@
 
-use crate::{RecordingState, MeetingControllerState};
+use crate::{RecordingState, MeetingControllerState, TranscriptionJobControllerState};
 use crate::audio::recorder::State;
 use anyhow::Error;
 use super::{IpcResponse, GetTranscriptParams, GetRecordingStartParams};
-use crate::model::Meeting;
+use crate::model::{Meeting, TranscriptionJob};
 use log::info;
-use tauri::Window;
+use tauri::{AppHandle, Manager, Window};
 use crate::audio::cpal_audio::cpal_utils;
 
 #[tauri::command]
@@ -73,14 +73,28 @@ pub fn stop_recording(
 #[tauri::command]
 pub async fn transcribe_recording(
     params: GetTranscriptParams,
+    app: AppHandle,
 ) -> IpcResponse<String> {
     /*
         Designed to simply return the transcript of a given recording.
-        The caller need to associate the transcript with the recording.
-        ? Simplify a lot the logic, other wise the function would need to bloc de meeting data structure.
+        When a meeting id is provided, the transcription is tracked as a job and
+        the transcript is attached to the meeting, even if the app is closed in between.
      */
     info!("transcribe_recording called");
-    let result = crate::audio::processor::get_transcript(&params.path, Some(params.language)).await;
+    let result = match params.meeting_id {
+        Some(meeting_id) => {
+            let job = {
+                let job_controller_state = app.state::<TranscriptionJobControllerState>();
+                let mut job_controller = job_controller_state.0.lock().unwrap();
+                job_controller.add(TranscriptionJob::new(meeting_id, params.path, Some(params.language)))
+            };
+            match job {
+                Ok(job) => crate::transcriber::job::run_job(app, job.get_uuid()).await,
+                Err(error) => Err(error),
+            }
+        },
+        None => crate::audio::processor::get_transcript(&params.path, Some(params.language)).await,
+    };
     info!("transcribe_recording result: {:?}", result);
     return IpcResponse::from(result);
 }
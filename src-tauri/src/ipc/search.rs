@@ -0,0 +1,51 @@
+use super::{IpcResponse, ModelMutateResultData, SemanticSearchParams};
+use crate::{MeetingControllerState, SearchIndexState, SettingControllerState};
+use crate::search::embedding;
+use crate::search::index::SearchResult;
+use log::info;
+use tauri::command;
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+
+#[command(async)]
+pub fn semantic_search_meetings(
+    params: SemanticSearchParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>,
+    setting_controller_state: tauri::State<SettingControllerState>,
+    search_index_state: tauri::State<SearchIndexState>
+) -> IpcResponse<Vec<SearchResult>> {
+    // Meetings changed since the last search are embedded first - Can take a while the first time
+    info!("semantic_search_meetings called");
+    let meeting_ids: Vec<String> = meeting_controller_state.0.lock().unwrap().meetings.keys().cloned().collect();
+    let setting = setting_controller_state.0.lock().unwrap().get_setting();
+    let embedder = match setting.and_then(|setting| embedding::from_setting(&setting)) {
+        Ok(embedder) => embedder,
+        Err(error) => return IpcResponse::from(Err(error)),
+    };
+    let mut index = search_index_state.0.lock().unwrap();
+    if let Err(error) = index.refresh(meeting_ids, embedder.as_ref()) {
+        return IpcResponse::from(Err(error));
+    }
+    return IpcResponse::from(index.search(&params.query, params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT), embedder.as_ref()));
+}
+
+#[command(async)]
+pub fn rebuild_search_index(
+    meeting_controller_state: tauri::State<MeetingControllerState>,
+    setting_controller_state: tauri::State<SettingControllerState>,
+    search_index_state: tauri::State<SearchIndexState>
+) -> IpcResponse<ModelMutateResultData> {
+    info!("rebuild_search_index called");
+    let meeting_ids: Vec<String> = meeting_controller_state.0.lock().unwrap().meetings.keys().cloned().collect();
+    let setting = setting_controller_state.0.lock().unwrap().get_setting();
+    let embedder = match setting.and_then(|setting| embedding::from_setting(&setting)) {
+        Ok(embedder) => embedder,
+        Err(error) => return IpcResponse::from(Err(error)),
+    };
+    let mut index = search_index_state.0.lock().unwrap();
+    let result = index.clear()
+        .and_then(|_| index.refresh(meeting_ids, embedder.as_ref()))
+        .map(|embedded| ModelMutateResultData { id: embedded.to_string() });
+    return IpcResponse::from(result);
+}
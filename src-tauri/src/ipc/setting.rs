@@ -1,7 +1,7 @@
-use super::{IpcResponse, UpdateParams};
+use super::{ExportPromptPackParams, ImportPromptPackParams, IpcResponse, UpdateParams};
 use crate::SettingControllerState;
 use tauri::command;
-use crate::model::Setting;
+use crate::model::{PromptImportReport, Setting};
 
 
 #[command]
@@ -23,6 +23,26 @@ pub fn update_setting(
 }
 
 
+#[command]
+pub fn export_prompt_pack(
+    params: ExportPromptPackParams,
+    setting_controller_state: tauri::State<SettingControllerState>
+) -> IpcResponse<String> {
+    let setting_controller = setting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(setting_controller.export_prompts(params.format, params.names));
+}
+
+
+#[command]
+pub fn import_prompt_pack(
+    params: ImportPromptPackParams,
+    setting_controller_state: tauri::State<SettingControllerState>
+) -> IpcResponse<PromptImportReport> {
+    let mut setting_controller = setting_controller_state.0.lock().unwrap();
+    return IpcResponse::from(setting_controller.import_prompts(params.path));
+}
+
+
 #[command]
 pub fn open_data_folder(
     setting_controller_state: tauri::State<SettingControllerState>
@@ -0,0 +1,56 @@
+use super::{GetParams, IpcResponse};
+use crate::{MeetingControllerState, SettingControllerState, TranscriptionJobControllerState};
+use crate::model::TranscriptionJob;
+use crate::transcriber::correction::{self, CorrectionPreview};
+use crate::transcriber::language::{self, TranscriptionLanguage};
+use log::info;
+use tauri::command;
+
+
+#[command]
+pub fn list_transcription_jobs(
+    job_controller_state: tauri::State<TranscriptionJobControllerState>
+) -> IpcResponse<Vec<TranscriptionJob>> {
+    info!("list_transcription_jobs called");
+    let job_controller = job_controller_state.0.lock().unwrap();
+    return IpcResponse::from(job_controller.list());
+}
+
+#[command]
+pub fn cancel_transcription_job(
+    params: GetParams,
+    job_controller_state: tauri::State<TranscriptionJobControllerState>
+) -> IpcResponse<TranscriptionJob> {
+    info!("cancel_transcription_job called");
+    let mut job_controller = job_controller_state.0.lock().unwrap();
+    return IpcResponse::from(job_controller.cancel(params.id));
+}
+
+#[command]
+pub fn list_transcription_languages() -> IpcResponse<Vec<TranscriptionLanguage>> {
+    info!("list_transcription_languages called");
+    let result: Result<Vec<TranscriptionLanguage>, anyhow::Error> = Ok(language::supported_languages());
+    return IpcResponse::from(result);
+}
+
+#[command]
+pub fn preview_corrections(
+    params: GetParams,
+    meeting_controller_state: tauri::State<MeetingControllerState>,
+    setting_controller_state: tauri::State<SettingControllerState>
+) -> IpcResponse<Vec<CorrectionPreview>> {
+    info!("preview_corrections called");
+    let meeting = {
+        let meeting_controller = meeting_controller_state.0.lock().unwrap();
+        meeting_controller.get(params.id)
+    };
+    let setting = {
+        let setting_controller = setting_controller_state.0.lock().unwrap();
+        setting_controller.get_setting()
+    };
+    let result = match (meeting, setting) {
+        (Ok(meeting), Ok(setting)) => correction::preview_rules(&meeting.transcript, &setting.correction_rules.unwrap_or_default(), &meeting.company_id),
+        (Err(error), _) | (_, Err(error)) => Err(error),
+    };
+    return IpcResponse::from(result);
+}
@@ -0,0 +1,13 @@
+use super::{IpcResponse, UsageReportParams};
+use crate::model::{UsageLedger, UsageReport};
+use log::info;
+use tauri::command;
+
+
+#[command]
+pub fn get_usage_report(
+    params: UsageReportParams
+) -> IpcResponse<UsageReport> {
+    info!("get_usage_report called");
+    return IpcResponse::from(UsageLedger::report(params.from, params.to));
+}
@@ -2,18 +2,24 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use audio::recorder::InnerRecordingState;
-use model::SettingController;
+use model::{SettingController, TranscriptionJobController};
 use utils::{filesys::local_data_dir_path, session::InnerSessionState};
 use tauri_plugin_log::{LogTarget, fern::colors::ColoredLevelConfig};
 use crate::model::MeetingController;
+use std::collections::HashMap;
 use std::sync::{Mutex, Arc};
+use transcriber::CancelFlag;
+use search::index::SearchIndex;
 
 
 mod audio;
 mod model;
 mod summarizer;
+mod search;
+mod transcriber;
 mod ipc;
 mod crm;
+mod export;
 mod utils;
 
 // the payload type must implement `Serialize` and `Clone`.
@@ -32,6 +38,10 @@ pub struct RecordingState(pub Arc<Mutex<InnerRecordingState>>);
 pub struct SettingControllerState(pub Arc<Mutex<SettingController>>); 
 pub struct MeetingControllerState(pub Arc<Mutex<MeetingController>>);
 pub struct SeesionState(pub Mutex<InnerSessionState>);
+pub struct TranscriptionJobControllerState(pub Arc<Mutex<TranscriptionJobController>>);
+// stream id -> cancel flag of the summaries being generated
+pub struct SummaryStreamState(pub Mutex<HashMap<String, CancelFlag>>);
+pub struct SearchIndexState(pub Mutex<SearchIndex>);
 
 
 fn main() {
@@ -51,6 +61,15 @@ fn main() {
       .manage(MeetingControllerState(Arc::new(Mutex::new(MeetingController::new()))))
       .manage(SettingControllerState(Arc::new(Mutex::new(SettingController::new(model::SettingPath::Default)))))
       .manage(SeesionState(Mutex::new(InnerSessionState::new())))
+      .manage(TranscriptionJobControllerState(Arc::new(Mutex::new(TranscriptionJobController::new()))))
+      .manage(SummaryStreamState(Mutex::new(HashMap::new())))
+      .manage(SearchIndexState(Mutex::new(SearchIndex::load())))
+      .setup(|app| {
+          // Transcriptions still running on the provider side when the app was closed
+          transcriber::job::resume_pending_jobs(app.handle());
+          export::digest::start_scheduler(app.handle());
+          Ok(())
+      })
       .invoke_handler(tauri::generate_handler![
           // Recorder
           ipc::start_recording, 
@@ -61,6 +80,11 @@ fn main() {
           ipc::transcribe_recording,
           ipc::get_available_audio_devices,
           ipc::get_recording_device_names,
+          // Transcription
+          ipc::list_transcription_jobs,
+          ipc::cancel_transcription_job,
+          ipc::list_transcription_languages,
+          ipc::preview_corrections,
           // Meeting
           ipc::get_meeting,
           ipc::list_meetings,
@@ -71,13 +95,38 @@ fn main() {
           ipc::improve_note_meeting,
           ipc::async_summarize_meeting,
           ipc::async_improve_note_meeting,
+          ipc::cancel_summary_stream,
+          ipc::preview_prompt,
+          ipc::render_summary_html,
+          ipc::accept_note_revision,
+          ipc::reject_note_revision,
+          ipc::list_summary_versions,
+          ipc::diff_summary_versions,
+          ipc::restore_summary_version,
+          ipc::async_extract_action_items_meeting,
+          ipc::async_generate_metadata_meeting,
+          ipc::draft_follow_up,
+          ipc::export_follow_up,
+          ipc::summarize_company,
+          ipc::generate_digest,
+          ipc::set_action_item_done,
+          ipc::list_open_action_items,
+          ipc::ask_meeting,
+          ipc::get_meeting_conversation,
+          ipc::clear_meeting_conversation,
+          ipc::semantic_search_meetings,
+          ipc::rebuild_search_index,
+          ipc::get_usage_report,
           ipc::increment_async_ops_meeting,
           ipc::decrement_async_ops_meeting,
           ipc::delete_all_meeting,
           ipc::export_all_meeting,
+          ipc::export_transcript_meeting,
           // Setting
           ipc::get_setting,
           ipc::update_setting,
+          ipc::export_prompt_pack,
+          ipc::import_prompt_pack,
           ipc::open_data_folder,
           // CRM
           ipc::search_organizations_crm,
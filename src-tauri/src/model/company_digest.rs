@@ -0,0 +1,114 @@
+/*
+    * Company Digest Model
+    * Cumulative digest of every meeting with a company, cached until one of these meetings changes
+*/
+use std::{fs::File, io::Read, io::Write};
+use std::path::{Path, PathBuf};
+use anyhow::Error;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+use crate::model::Meeting;
+use crate::utils::filesys::{check_file_id, local_data_dir_path};
+use log::{info, warn, error};
+
+
+/// Meeting included in a digest, as it was when the digest was generated
+#[derive(Clone, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct DigestSource {
+    pub meeting_id: String,
+    pub title: String,
+    pub datetime: String,
+    pub fingerprint: String,
+}
+
+impl DigestSource {
+    pub fn from_meeting(meeting: &Meeting) -> Self {
+        // Saved with the digest -> A stable hash, so a new toolchain doesn't regenerate every digest
+        let mut hasher = Sha256::new();
+        for field in [&meeting.title, &meeting.datetime, &meeting.summary, &meeting.note, &meeting.transcript] {
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field.as_bytes());
+        }
+        Self {
+            meeting_id: meeting.get_uuid(),
+            title: meeting.title.clone(),
+            datetime: meeting.datetime.clone(),
+            fingerprint: format!("{:x}", hasher.finalize()),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct CompanyDigest {
+    pub company_id: String,
+    pub company_name: String,
+    pub digest: String, // Markdown
+    pub sources: Vec<DigestSource>, // Oldest meeting first
+    pub model: String,
+    pub created_at: String,
+}
+
+impl CompanyDigest {
+    pub fn new(company_id: String, company_name: String, digest: String, meetings: &Vec<Meeting>, model: String) -> Self {
+        Self {
+            company_id,
+            company_name,
+            digest,
+            sources: meetings.iter().map(DigestSource::from_meeting).collect(),
+            model,
+            created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        }
+    }
+
+    fn path(company_id: &String) -> Result<PathBuf, Error> {
+        check_file_id(company_id)?;
+        return Ok(local_data_dir_path().join("companyDigests").join(format!("{}.json", company_id)));
+    }
+
+    /// Cached digest - None if there is none or a meeting was added, removed or changed since
+    pub fn load_valid(company_id: &String, meetings: &Vec<Meeting>) -> Option<Self> {
+        let mut file = File::open(Self::path(company_id).ok()?).ok()?;
+        let mut contents = String::new();
+        let _ = file.read_to_string(&mut contents);
+        let digest: Self = match serde_json::from_str(&contents) {
+            Ok(digest) => digest,
+            Err(error) => {
+                error!("Error while parsing company digest {}.json: {:?}\n", company_id, error);
+                return None;
+            }
+        };
+        let sources: Vec<DigestSource> = meetings.iter().map(DigestSource::from_meeting).collect();
+        if digest.sources != sources {
+            info!("Company digest {} is outdated", company_id);
+            return None;
+        }
+        return Some(digest);
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let digest_path = Self::path(&self.company_id)?;
+        let parent_dir = Path::new(&digest_path).parent().unwrap();
+        if !parent_dir.exists() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
+        let mut file = File::create(&digest_path)?;
+        let serialized = serde_json::to_string(&self)?;
+        file.write_all(serialized.as_bytes())?;
+        return Ok(());
+    }
+
+    pub fn delete_from_disk(company_id: &String) {
+        let digest_path = match Self::path(company_id) {
+            Ok(digest_path) if digest_path.exists() => digest_path,
+            _ => return,
+        };
+        match std::fs::remove_file(digest_path) {
+            Ok(_) => info!("Company digest {}.json deleted successfully", company_id),
+            Err(error) => warn!("Error while deleting company digest {}.json: {:?}", company_id, error),
+        }
+    }
+}
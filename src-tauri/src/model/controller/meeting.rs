@@ -1,12 +1,20 @@
 
 use std::collections::HashMap;
 use std::fs;
-use std::process::Command;
 use std::{fs::File, io::Read, io::Write, io};
 use crate::ipc::ModelMutateResultData;
-use crate::model::Meeting;
-use crate::utils::filesys::local_data_dir_path;
+use crate::model::{ActionItem, CompanyDigest, DigestSource, Meeting, NoteRevision, Setting, SummaryHistory, SummaryInfo, SummaryOrigin, SummaryVersion};
+use crate::utils::diff::{diff_lines, DiffLine};
+use crate::utils::markdown;
+use crate::export::digest::{self, PeriodDigest};
+use crate::export::email::{self, FollowUpFormat};
+use crate::export::transcript::{self, TranscriptFormat};
+use crate::summarizer::SummaryObserver;
+use crate::summarizer::metadata::MeetingMetadata;
+use crate::summarizer::template::PromptPreview;
+use crate::utils::filesys::{local_data_dir_path, reveal_in_file_manager};
 use anyhow::Error;
+use chrono::NaiveDate;
 use log::{ warn, error, info};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
@@ -22,6 +30,19 @@ pub struct MeetingsRef {
     pub number_ops: i32,
 }
 
+#[derive(Clone, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct OpenActionItem {
+    pub meeting_id: String,
+    pub meeting_title: String,
+    pub meeting_datetime: String,
+    pub company_name: String,
+    pub item: ActionItem,
+}
+
+// The UI save the meeting at each change - Edits made within this time are a single version
+const EDIT_SESSION_MINUTES: i64 = 10;
+
 fn default_number_ops() -> i32 {0}
 
 #[derive(Deserialize, Serialize)]
@@ -93,9 +114,37 @@ impl MeetingController {
                 warn!("No local data - Error while loading meetingRef file: {:?}\n", error);
             }
         }
+        s.migrate_html_summaries();
         return s;
     }
 
+    /// Summaries used to be HTML -> Converted once to Markdown, before anything else reads the meetings
+    /// The HTML summary is kept in the history so it can be restored
+    fn migrate_html_summaries(&self) {
+        for uuid in self.meetings.keys() {
+            let mut meeting = match Meeting::load(uuid.clone()) {
+                Ok(meeting) => meeting,
+                Err(_) => continue,
+            };
+            if !markdown::looks_like_html(&meeting.summary) {
+                continue;
+            }
+            info!("Converting the HTML summary of meeting {} to Markdown", uuid);
+            let mut history = match SummaryHistory::load(uuid.clone()) {
+                Ok(history) => history,
+                Err(error) => {
+                    error!("Summary of meeting {} not converted, its history can't be read: {:?}", uuid, error);
+                    continue;
+                }
+            };
+            history.push(meeting.summary.clone(), meeting.summary_info.clone());
+            meeting.summary = markdown::from_html(&meeting.summary);
+            if let Err(error) = history.save().and_then(|_| meeting.save()) {
+                error!("Error while converting the summary of meeting {} to Markdown: {:?}", uuid, error);
+            }
+        }
+    }
+
     pub fn save(&self) -> Result<(), Error> {
         let serialized = serde_json::to_string(&self.meetings)?;
         let local_data_path = local_data_dir_path().join("meetingsRef.json").to_str().unwrap().to_string();        
@@ -132,6 +181,17 @@ impl MeetingController {
         if !self.meetings.contains_key(&meeting.get_uuid()) {
             return Err(anyhow::anyhow!("Meeting does not exist"));
         }
+        let mut meeting = meeting;
+        meeting.summary = markdown::ensure_markdown(&meeting.summary);
+        if let Ok(stored) = Meeting::load(meeting.get_uuid()) {
+            // The window may send a meeting loaded before a note revision was proposed
+            meeting.note_revision = stored.note_revision.clone();
+            if DigestSource::from_meeting(&stored) != DigestSource::from_meeting(&meeting) || stored.company_id != meeting.company_id {
+                CompanyDigest::delete_from_disk(&stored.company_id);
+                CompanyDigest::delete_from_disk(&meeting.company_id);
+            }
+        }
+        self.record_summary_version(&mut meeting)?;
         let _ = self.meetings.insert(
             meeting.get_uuid(), 
             MeetingInfo {
@@ -144,6 +204,68 @@ impl MeetingController {
         return Ok(ModelMutateResultData { id: meeting.get_uuid() });
     }
 
+    /// Keep the summary about to be replaced in the history of the meeting
+    /// -> A summary changed without new info was written by the user
+    fn record_summary_version(&self, meeting: &mut Meeting) -> Result<(), Error> {
+        let stored = match Meeting::load(meeting.get_uuid()) {
+            Ok(stored) => stored,
+            Err(_) => return Ok(()),
+        };
+        if stored.summary == meeting.summary {
+            return Ok(());
+        }
+        if meeting.summary_info == stored.summary_info {
+            if let Some(info) = &stored.summary_info {
+                let since = chrono::DateTime::parse_from_rfc3339(&info.created_at)
+                    .map(|created_at| chrono::Utc::now().signed_duration_since(created_at))
+                    .unwrap_or(chrono::Duration::max_value());
+                if info.origin == SummaryOrigin::User && since < chrono::Duration::minutes(EDIT_SESSION_MINUTES) {
+                    return Ok(());
+                }
+            }
+            meeting.summary_info = Some(SummaryInfo::user());
+        }
+        if stored.summary.trim().is_empty() {
+            return Ok(());
+        }
+        let mut history = SummaryHistory::load(meeting.get_uuid())?;
+        history.push(stored.summary, stored.summary_info);
+        return history.save();
+    }
+
+    pub fn list_summary_versions(&self, uuid: String) -> Result<Vec<SummaryVersion>, Error> {
+        let mut versions = SummaryHistory::load(uuid)?.versions;
+        versions.reverse();
+        return Ok(versions);
+    }
+
+    /// `None` is the current summary
+    pub fn diff_summary_versions(&self, uuid: String, from: Option<String>, to: Option<String>) -> Result<Vec<DiffLine>, Error> {
+        let meeting = self.get(uuid.clone())?;
+        let history = SummaryHistory::load(uuid)?;
+        let from = match from {
+            Some(version_id) => markdown::ensure_markdown(&history.get(&version_id)?.summary),
+            None => meeting.summary.clone(),
+        };
+        let to = match to {
+            Some(version_id) => markdown::ensure_markdown(&history.get(&version_id)?.summary),
+            None => meeting.summary.clone(),
+        };
+        return Ok(diff_lines(&from, &to));
+    }
+
+    /// The current summary become a version, so a restore can be undone
+    pub fn restore_summary_version(&mut self, uuid: String, version_id: String) -> Result<ModelMutateResultData, Error> {
+        let mut meeting = self.get(uuid.clone())?;
+        let version = SummaryHistory::load(uuid)?.get(&version_id)?;
+        meeting.summary = version.summary;
+        meeting.summary_info = Some(match version.info {
+            Some(info) => SummaryInfo { created_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true), ..info },
+            None => SummaryInfo::user(),
+        });
+        return self.update(meeting);
+    }
+
     pub fn add(&mut self, meeting: Meeting) -> Result<Meeting, Error> {
         if self.meetings.contains_key(&meeting.get_uuid()) {
             return Err(anyhow::anyhow!("Meeting already exists"));
@@ -236,32 +358,196 @@ impl MeetingController {
         info!("Files compressed successfully to {:?}", zip_file_path); 
 
         // Open the folder containing the zip file for the user to find it
-        #[cfg(target_family = "windows")]
-        Command::new("explorer")
-        .args(["/select,", zip_file_path.to_str().unwrap()]) // The comma after select is not a typo
-        .spawn()
-        .unwrap();
-
-        #[cfg(target_os = "macos")]
-        Command::new("open")
-        .args(["-R", zip_file_path.to_str().unwrap()])
-        .spawn()
-        .unwrap();
+        reveal_in_file_manager(zip_file_path);
 
         return Ok(zip_file_path.to_str().unwrap().to_string());
     }
 
+    pub fn export_transcript(&self, uuid: String, format: TranscriptFormat) -> Result<String, Error> {
+        // Write the transcript in the export folder
+        // -> Return the path to the file created
+        let meeting = self.get(uuid.clone())?;
+        let content = transcript::render(&meeting, &format)?;
+        let file_path = local_data_dir_path().join("export").join(format!("transcript_{}.{}", uuid, format.extension()));
+        let parent_dir = file_path.parent().unwrap();
+        if !parent_dir.exists() {std::fs::create_dir_all(parent_dir)?;}
+        let mut file = File::create(&file_path)?;
+        file.write_all(content.as_bytes())?;
+        info!("Transcript exported to {:?}", file_path);
+
+        reveal_in_file_manager(&file_path);
+
+        return Ok(file_path.to_str().unwrap().to_string());
+    }
+
+    /// `.eml` is written in the export folder -> Return its path, or the `mailto:` link
+    pub fn export_follow_up(&self, uuid: String, format: FollowUpFormat) -> Result<String, Error> {
+        let meeting = self.get(uuid.clone())?;
+        let content = email::render(&meeting.follow_up, &format)?;
+        match format {
+            FollowUpFormat::Mailto => return Ok(content),
+            FollowUpFormat::Eml => {
+                let file_path = local_data_dir_path().join("export").join(format!("follow_up_{}.eml", uuid));
+                let parent_dir = file_path.parent().unwrap();
+                if !parent_dir.exists() {std::fs::create_dir_all(parent_dir)?;}
+                let mut file = File::create(&file_path)?;
+                file.write_all(content.as_bytes())?;
+                info!("Follow-up email exported to {:?}", file_path);
+
+                reveal_in_file_manager(&file_path);
+
+                return Ok(file_path.to_str().unwrap().to_string());
+            }
+        }
+    }
 
-    pub fn summarize_meeting(&mut self, uuid: String) -> Result<ModelMutateResultData, Error> {
+    pub fn summarize_meeting(&mut self, uuid: String, force: bool) -> Result<ModelMutateResultData, Error> {
         let mut meeting = self.get(uuid.clone())?;
-        meeting.summarize()?;
+        meeting.summarize(&SummaryObserver::default(), force)?;
         self.update(meeting)
     }
 
-    pub fn improve_note_meeting(&mut self, uuid: String) -> Result<ModelMutateResultData, Error> {
+    pub fn preview_prompt(&self, uuid: String) -> Result<PromptPreview, Error> {
+        let meeting = self.get(uuid)?;
+        return meeting.preview_prompt();
+    }
+
+    /// Summaries are Markdown - Sanitized HTML for the UI
+    pub fn render_summary_html(&self, uuid: String) -> Result<String, Error> {
+        let meeting = self.get(uuid)?;
+        return Ok(markdown::to_html(&meeting.summary));
+    }
+
+    pub fn improve_note_meeting(&mut self, uuid: String, force: bool) -> Result<ModelMutateResultData, Error> {
         let mut meeting = self.get(uuid.clone())?;
-        meeting.improve_note()?;
-        self.update(meeting)
+        meeting.improve_note(&SummaryObserver::default(), force)?;
+        self.set_note_revision(uuid, meeting.note_revision)
+    }
+
+    pub fn apply_metadata(&mut self, uuid: String, metadata: &MeetingMetadata, setting: &Setting) -> Result<ModelMutateResultData, Error> {
+        let mut meeting = self.get(uuid)?;
+        meeting.apply_metadata(metadata, setting);
+        return self.update(meeting);
+    }
+
+    /// The revision is only changed here - `update` keeps the stored one
+    pub fn set_note_revision(&mut self, uuid: String, revision: Option<NoteRevision>) -> Result<ModelMutateResultData, Error> {
+        let mut meeting = self.get(uuid)?;
+        meeting.note_revision = revision;
+        meeting.save()?;
+        return Ok(ModelMutateResultData { id: meeting.get_uuid() });
+    }
+
+    pub fn accept_note_revision(&mut self, uuid: String, lines: Option<Vec<usize>>) -> Result<ModelMutateResultData, Error> {
+        let mut meeting = self.get(uuid)?;
+        meeting.accept_note_revision(lines)?;
+        meeting.save()?;
+        return self.update(meeting);
+    }
+
+    pub fn reject_note_revision(&mut self, uuid: String) -> Result<ModelMutateResultData, Error> {
+        let mut meeting = self.get(uuid)?;
+        meeting.reject_note_revision()?;
+        meeting.save()?;
+        return Ok(ModelMutateResultData { id: meeting.get_uuid() });
+    }
+
+    pub fn set_action_item_done(&mut self, uuid: String, item_id: String, done: bool) -> Result<ActionItem, Error> {
+        let mut meeting = self.get(uuid)?;
+        let item = meeting.set_action_item_done(&item_id, done)?;
+        self.update(meeting)?;
+        return Ok(item);
+    }
+
+    fn digest_path(from: &NaiveDate, to: &NaiveDate) -> PathBuf {
+        return local_data_dir_path().join("digests").join(digest::file_name(from, to));
+    }
+
+    pub fn digest_exists(&self, from: &NaiveDate, to: &NaiveDate) -> bool {
+        return Self::digest_path(from, to).exists();
+    }
+
+    /// Report of the meetings between `from` & `to` included, written in the digests folder
+    pub fn period_digest(&self, from: NaiveDate, to: NaiveDate) -> Result<PeriodDigest, Error> {
+        if from > to {
+            return Err(anyhow::anyhow!("The start of the period is after its end"));
+        }
+        let mut meetings = Vec::new();
+        for uuid in self.meetings.keys() {
+            match self.get(uuid.clone()) {
+                Ok(meeting) => {
+                    if digest::meeting_date(&meeting).map(|date| date >= from && date <= to).unwrap_or(false) {
+                        meetings.push(meeting);
+                    }
+                },
+                Err(error) => warn!("Meeting {} skipped while writing the digest: {:?}", uuid, error),
+            }
+        }
+        meetings.sort_by(|a, b| a.datetime.cmp(&b.datetime));
+        let content = digest::render(&from, &to, &meetings);
+        let file_path = Self::digest_path(&from, &to);
+        let parent_dir = file_path.parent().unwrap();
+        if !parent_dir.exists() {std::fs::create_dir_all(parent_dir)?;}
+        let mut file = File::create(&file_path)?;
+        file.write_all(content.as_bytes())?;
+        info!("Digest written to {:?}", file_path);
+        return Ok(PeriodDigest {
+            from: from.format("%Y-%m-%d").to_string(),
+            to: to.format("%Y-%m-%d").to_string(),
+            meeting_count: meetings.len(),
+            path: file_path.to_str().unwrap().to_string(),
+            content,
+        });
+    }
+
+    /// Meetings of a company, oldest first
+    pub fn company_meetings(&self, company_id: &String) -> Result<Vec<Meeting>, Error> {
+        if company_id.trim().is_empty() {
+            return Err(anyhow::anyhow!("Company id is needed"));
+        }
+        let mut meetings = Vec::new();
+        for uuid in self.meetings.keys() {
+            match self.get(uuid.clone()) {
+                Ok(meeting) if &meeting.company_id == company_id => meetings.push(meeting),
+                Ok(_) => (),
+                Err(error) => warn!("Meeting {} skipped while gathering company meetings: {:?}", uuid, error),
+            }
+        }
+        if meetings.is_empty() {
+            return Err(anyhow::anyhow!("No meeting with this company"));
+        }
+        meetings.sort_by(|a, b| a.datetime.cmp(&b.datetime));
+        return Ok(meetings);
+    }
+
+    pub fn list_open_action_items(&self) -> Result<Vec<OpenActionItem>, Error> {
+        // Earliest due date first - Items without due date come last, most recent meeting first
+        let mut list = Vec::new();
+        for uuid in self.meetings.keys() {
+            let meeting = match self.get(uuid.clone()) {
+                Ok(meeting) => meeting,
+                Err(error) => {
+                    warn!("Meeting {} skipped while listing action items: {:?}", uuid, error);
+                    continue;
+                }
+            };
+            for item in meeting.action_items.clone().unwrap_or_default().into_iter().filter(|item| !item.done) {
+                list.push(OpenActionItem {
+                    meeting_id: meeting.get_uuid(),
+                    meeting_title: meeting.title.clone(),
+                    meeting_datetime: meeting.datetime.clone(),
+                    company_name: meeting.company_name.clone(),
+                    item,
+                });
+            }
+        }
+        list.sort_by(|a, b| match (&a.item.due_date, &b.item.due_date) {
+            (Some(a_due), Some(b_due)) => a_due.cmp(b_due),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => b.meeting_datetime.cmp(&a.meeting_datetime),
+        });
+        return Ok(list);
     }
 
     // Async ops - Used to know if a meeting is being processed by a background task (Used in UI to display a spinner)
@@ -1,3 +1,7 @@
 
 mod meeting;
-pub use meeting::*;
\ No newline at end of file
+mod transcription_job;
+mod usage;
+pub use meeting::*;
+pub use transcription_job::*;
+pub use usage::*;
\ No newline at end of file
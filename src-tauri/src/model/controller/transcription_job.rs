@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::{fs::File, io::Read, io::Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::model::{RedactionPolicy, TranscriptionJob, TranscriptionStatus};
+use crate::transcriber::CancelFlag;
+use crate::utils::filesys::local_data_dir_path;
+use anyhow::Error;
+use log::{warn, error, info};
+
+
+pub struct TranscriptionJobController {
+    // uuid -> job
+    pub jobs: HashMap<String, TranscriptionJob>,
+    // uuid -> cancel flag of the running task (not saved to disk)
+    cancel_flags: HashMap<String, CancelFlag>,
+}
+
+impl TranscriptionJobController {
+    pub fn new() -> TranscriptionJobController {
+        let local_data_path = local_data_dir_path().join("transcriptionJobs.json").to_str().unwrap().to_string();
+        let file = File::open(local_data_path);
+        let mut s = Self {
+            jobs: HashMap::new(),
+            cancel_flags: HashMap::new(),
+        };
+        match file {
+            Ok(mut file) => {
+                let mut contents: String = String::new();
+                let _ = file.read_to_string(&mut contents);
+                match serde_json::from_str(&contents) {
+                    Ok(jobs) => {
+                        info!("Loaded transcriptionJobs file");
+                        s.jobs = jobs;
+                    },
+                    Err(error) => {
+                        error!("Error while parsing transcriptionJobs file: {:?}\n", error);
+                    }
+                }
+            },
+            Err(error) => {
+                warn!("No local data - Error while loading transcriptionJobs file: {:?}\n", error);
+            }
+        }
+        return s;
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let serialized = serde_json::to_string(&self.jobs)?;
+        let local_data_path = local_data_dir_path().join("transcriptionJobs.json").to_str().unwrap().to_string();
+        let parent_dir = Path::new(&local_data_path).parent().unwrap();
+        if !parent_dir.exists() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
+        let mut file = File::create(&local_data_path)?;
+        file.write_all(serialized.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn list(&self) -> Result<Vec<TranscriptionJob>, Error> {
+        let mut list: Vec<TranscriptionJob> = self.jobs.values().cloned().collect();
+        list.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        return Ok(list);
+    }
+
+    pub fn get(&self, uuid: String) -> Result<TranscriptionJob, Error> {
+        match self.jobs.get(&uuid) {
+            Some(job) => Ok(job.clone()),
+            None => Err(anyhow::anyhow!("Transcription job does not exist")),
+        }
+    }
+
+    pub fn add(&mut self, job: TranscriptionJob) -> Result<TranscriptionJob, Error> {
+        if self.jobs.contains_key(&job.get_uuid()) {
+            return Err(anyhow::anyhow!("Transcription job already exists"));
+        }
+        self.jobs.insert(job.get_uuid(), job.clone());
+        self.save()?;
+        return Ok(job);
+    }
+
+    pub fn update(&mut self, job: TranscriptionJob) -> Result<TranscriptionJob, Error> {
+        if !self.jobs.contains_key(&job.get_uuid()) {
+            return Err(anyhow::anyhow!("Transcription job does not exist"));
+        }
+        self.jobs.insert(job.get_uuid(), job.clone());
+        self.save()?;
+        return Ok(job);
+    }
+
+    pub fn set_provider_job_id(&mut self, uuid: String, provider_job_id: String, redaction: Option<RedactionPolicy>) -> Result<TranscriptionJob, Error> {
+        let mut job = self.get(uuid)?;
+        job.provider_job_id = Some(provider_job_id);
+        job.redaction = redaction;
+        job.set_status(TranscriptionStatus::Processing);
+        return self.update(job);
+    }
+
+    pub fn fail(&mut self, uuid: String, error: String) -> Result<TranscriptionJob, Error> {
+        let mut job = self.get(uuid)?;
+        job.error = Some(error);
+        job.set_status(TranscriptionStatus::Error);
+        return self.update(job);
+    }
+
+    pub fn complete(&mut self, uuid: String) -> Result<TranscriptionJob, Error> {
+        let mut job = self.get(uuid)?;
+        job.error = None;
+        job.set_status(TranscriptionStatus::Completed);
+        return self.update(job);
+    }
+
+    pub fn cancelled(&mut self, uuid: String) -> Result<TranscriptionJob, Error> {
+        let mut job = self.get(uuid)?;
+        job.set_status(TranscriptionStatus::Cancelled);
+        return self.update(job);
+    }
+
+    pub fn register_cancel_flag(&mut self, uuid: String) -> CancelFlag {
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.cancel_flags.insert(uuid, cancel.clone());
+        return cancel;
+    }
+
+    pub fn release_cancel_flag(&mut self, uuid: String) {
+        self.cancel_flags.remove(&uuid);
+    }
+
+    pub fn cancel(&mut self, uuid: String) -> Result<TranscriptionJob, Error> {
+        let job = self.get(uuid.clone())?;
+        if !job.is_pending() {
+            return Err(anyhow::anyhow!("Transcription job is not running"));
+        }
+        match self.cancel_flags.get(&uuid) {
+            // The running task will stop at the next chunk or polling
+            Some(cancel) => {
+                cancel.store(true, Ordering::SeqCst);
+                return Ok(job);
+            },
+            None => return self.cancelled(uuid),
+        }
+    }
+
+    pub fn pending(&self) -> Vec<TranscriptionJob> {
+        return self.jobs.values().filter(|job| job.is_pending()).cloned().collect();
+    }
+
+}
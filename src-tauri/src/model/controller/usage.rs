@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use anyhow::Error;
+use chrono::{Datelike, TimeZone, Utc};
+use log::{error, warn};
+use serde::Serialize;
+use ts_rs::TS;
+use crate::model::{SettingController, UsageEntry, UsageKind};
+use crate::utils::filesys::local_data_dir_path;
+
+
+#[derive(Clone, Default, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct UsageTotal {
+    pub key: String, // Provider name or meeting uuid
+    #[ts(type = "number")]
+    pub prompt_tokens: u64,
+    #[ts(type = "number")]
+    pub completion_tokens: u64,
+    pub audio_minutes: f64,
+    pub estimated_cost: f64,
+}
+
+impl UsageTotal {
+    fn add(&mut self, entry: &UsageEntry) {
+        self.prompt_tokens += entry.prompt_tokens as u64;
+        self.completion_tokens += entry.completion_tokens as u64;
+        self.audio_minutes += entry.audio_seconds / 60_f64;
+        self.estimated_cost += entry.estimated_cost;
+    }
+}
+
+#[derive(Clone, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct UsageReport {
+    pub from: String,
+    pub to: String,
+    pub total: UsageTotal,
+    pub by_provider: Vec<UsageTotal>,
+    pub by_meeting: Vec<UsageTotal>,
+    // Spent this month & caps of the settings
+    pub month_llm_cost: f64,
+    pub month_transcription_cost: f64,
+    pub monthly_llm_cap: Option<f64>,
+    pub monthly_transcription_cap: Option<f64>,
+}
+
+/// Append-only ledger in usage.jsonl - One line per entry, so recording never rewrite the file
+pub struct UsageLedger;
+
+impl UsageLedger {
+    fn path() -> PathBuf {
+        return local_data_dir_path().join("usage.jsonl");
+    }
+
+    fn append(entry: &UsageEntry) -> Result<(), Error> {
+        let path = Self::path();
+        let parent_dir = path.parent().unwrap();
+        if !parent_dir.exists() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(format!("{}\n", serde_json::to_string(entry)?).as_bytes())?;
+        return Ok(());
+    }
+
+    pub fn record(entry: UsageEntry) {
+        // Losing an entry must not fail the job which was already paid
+        if let Err(error) = Self::append(&entry) {
+            error!("Error while recording usage: {:?}", error);
+        }
+    }
+
+    pub fn entries() -> Vec<UsageEntry> {
+        let file = match File::open(Self::path()) {
+            Ok(file) => file,
+            Err(_) => return Vec::new(),
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(error) => warn!("Usage entry skipped: {:?}", error),
+            }
+        }
+        return entries;
+    }
+
+    fn month_start() -> String {
+        let now = Utc::now();
+        return Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0).unwrap().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    }
+
+    pub fn month_cost(kind: UsageKind) -> f64 {
+        let month_start = Self::month_start();
+        return Self::entries().iter()
+            .filter(|entry| entry.kind == kind && entry.created_at >= month_start)
+            .map(|entry| entry.estimated_cost)
+            .sum();
+    }
+
+    /// Called before starting a job billed by a provider
+    pub fn check_budget(kind: UsageKind) -> Result<(), Error> {
+        let setting = SettingController::new(crate::model::SettingPath::Default).get_setting()?;
+        let budget = match setting.budget {
+            Some(budget) => budget,
+            None => return Ok(()),
+        };
+        let (cap, label) = match kind {
+            UsageKind::Llm => (budget.monthly_llm_cap, "LLM"),
+            UsageKind::Transcription => (budget.monthly_transcription_cap, "transcription"),
+        };
+        if let Some(cap) = cap {
+            let spent = Self::month_cost(kind);
+            if spent >= cap {
+                return Err(anyhow::anyhow!(
+                    "Monthly {} budget reached: ${:.2} spent of ${:.2} since {} - Raise the cap in the settings to continue",
+                    label, spent, cap, &Self::month_start()[..10]
+                ));
+            }
+        }
+        return Ok(());
+    }
+
+    /// Dates are RFC 3339 - From the start of the month to now if not set
+    pub fn report(from: Option<String>, to: Option<String>) -> Result<UsageReport, Error> {
+        let from = from.unwrap_or(Self::month_start());
+        let to = to.unwrap_or(Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        let entries = Self::entries();
+        let mut total = UsageTotal { key: String::from("total"), ..Default::default() };
+        let mut by_provider: HashMap<String, UsageTotal> = HashMap::new();
+        let mut by_meeting: HashMap<String, UsageTotal> = HashMap::new();
+        for entry in entries.iter().filter(|entry| entry.created_at >= from && entry.created_at <= to) {
+            total.add(entry);
+            by_provider.entry(entry.provider.clone())
+                .or_insert(UsageTotal { key: entry.provider.clone(), ..Default::default() })
+                .add(entry);
+            if let Some(meeting_id) = &entry.meeting_id {
+                by_meeting.entry(meeting_id.clone())
+                    .or_insert(UsageTotal { key: meeting_id.clone(), ..Default::default() })
+                    .add(entry);
+            }
+        }
+        let sort = |totals: HashMap<String, UsageTotal>| {
+            let mut totals: Vec<UsageTotal> = totals.into_values().collect();
+            totals.sort_by(|a, b| b.estimated_cost.partial_cmp(&a.estimated_cost).unwrap_or(std::cmp::Ordering::Equal));
+            totals
+        };
+        let setting = SettingController::new(crate::model::SettingPath::Default).get_setting()?;
+        let budget = setting.budget.unwrap_or_default();
+        return Ok(UsageReport {
+            from,
+            to,
+            total,
+            by_provider: sort(by_provider),
+            by_meeting: sort(by_meeting),
+            month_llm_cost: Self::month_cost(UsageKind::Llm),
+            month_transcription_cost: Self::month_cost(UsageKind::Transcription),
+            monthly_llm_cap: budget.monthly_llm_cap,
+            monthly_transcription_cap: budget.monthly_transcription_cap,
+        });
+    }
+}
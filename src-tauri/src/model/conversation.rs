@@ -0,0 +1,96 @@
+/*
+    * Conversation Model
+    * Questions asked about a meeting and the answers of the model, saved next to the meeting
+*/
+use std::{fs::File, io::Read, io::Write};
+use std::path::{Path, PathBuf};
+use anyhow::Error;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use crate::utils::filesys::{check_file_id, local_data_dir_path};
+use log::{info, warn, error};
+
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum ConversationRole {
+    User,
+    Assistant,
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct ConversationMessage {
+    pub role: ConversationRole,
+    pub content: String,
+    pub quotes: Vec<String>, // Transcript passages the answer rely on
+    pub created_at: String,
+}
+
+impl ConversationMessage {
+    pub fn new(role: ConversationRole, content: String, quotes: Vec<String>) -> Self {
+        Self {
+            role,
+            content,
+            quotes,
+            created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct Conversation {
+    pub meeting_id: String,
+    pub messages: Vec<ConversationMessage>,
+}
+
+impl Conversation {
+    fn path(meeting_id: &String) -> Result<PathBuf, Error> {
+        check_file_id(meeting_id)?;
+        return Ok(local_data_dir_path().join("conversations").join(format!("{}.json", meeting_id)));
+    }
+
+    /// A meeting without conversation file has an empty conversation
+    pub fn load(meeting_id: String) -> Result<Self, Error> {
+        let file = File::open(Self::path(&meeting_id)?);
+        match file {
+            Ok(mut file) => {
+                let mut contents = String::new();
+                let _ = file.read_to_string(&mut contents);
+                match serde_json::from_str(&contents) {
+                    Ok(conversation) => Ok(conversation),
+                    Err(error) => {
+                        error!("Error while parsing conversation {}.json: {:?}\n", meeting_id, error);
+                        Err(error.into())
+                    }
+                }
+            },
+            Err(_) => Ok(Self { meeting_id, messages: Vec::new() }),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let conversation_path = Self::path(&self.meeting_id)?;
+        let parent_dir = Path::new(&conversation_path).parent().unwrap();
+        if !parent_dir.exists() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
+        let mut file = File::create(&conversation_path)?;
+        let serialized = serde_json::to_string(&self)?;
+        file.write_all(serialized.as_bytes())?;
+        return Ok(());
+    }
+
+    pub fn delete_from_disk(meeting_id: &String) {
+        let conversation_path = match Self::path(meeting_id) {
+            Ok(conversation_path) if conversation_path.exists() => conversation_path,
+            _ => return,
+        };
+        match std::fs::remove_file(conversation_path) {
+            Ok(_) => info!("Conversation {}.json deleted successfully", meeting_id),
+            Err(error) => warn!("Error while deleting conversation {}.json: {:?}", meeting_id, error),
+        }
+    }
+}
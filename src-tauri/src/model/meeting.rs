@@ -9,8 +9,15 @@ use uuid::Uuid;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
+use crate::model::{CompanyDigest, Conversation, Setting, SettingController, RedactionPolicy, SummaryHistory, SummaryInfo, UsageContext, UsageOperation};
 use crate::utils::filesys::local_data_dir_path; 
-use crate::summarizer::openai::summarize_with_openai;
+use crate::utils::markdown;
+use crate::utils::diff::{apply_diff, diff_lines, DiffLine};
+use crate::summarizer::cache;
+use crate::summarizer::{preview_prompt, summarize_transcript, SummaryObserver};
+use crate::summarizer::template::{render_template, PromptPreview};
+use crate::summarizer::action_items::ExtractedActionItem;
+use crate::summarizer::metadata::MeetingMetadata;
 use std::path::Path;
 use log::{info, warn, error};
 
@@ -31,6 +38,16 @@ pub struct Meeting {
     pub published: bool,
     pub publish_with_note: Option<bool>,
     chapters: Vec<Chapter>,
+    pub detected_language: Option<String>,
+    pub language_confidence: Option<f32>,
+    pub segments: Option<Vec<TranscriptSegment>>,
+    pub redaction: Option<AppliedRedaction>,
+    pub action_items: Option<Vec<ActionItem>>,
+    pub summary_info: Option<SummaryInfo>,
+    pub note_revision: Option<NoteRevision>,
+    pub participants: Option<Vec<String>>,
+    pub meeting_type: Option<String>, // One of the types of the prompt rules
+    pub follow_up: Option<FollowUpDraft>,
 }
 
 #[derive(Clone, Deserialize, Serialize, TS)]
@@ -43,6 +60,65 @@ pub struct Chapter {
     end: i32,
 }
 
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct TranscriptSegment {
+    pub text: String,
+    pub start: i32, // ms
+    pub end: i32,   // ms
+    pub speaker: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct AppliedRedaction {
+    pub policy: RedactionPolicy,
+    pub audio_redacted: bool,
+    pub applied_at: String,
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct ActionItem {
+    pub uuid: String,
+    pub task: String,
+    pub owner: Option<String>,
+    pub due_date: Option<String>, // YYYY-MM-DD
+    pub source_quote: Option<String>,
+    pub done: bool,
+    pub done_at: Option<String>,
+}
+
+/// Improved note proposed by the model - The note is only replaced once accepted
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct NoteRevision {
+    pub original: String,
+    pub proposed: String,
+    pub diff: Vec<DiffLine>, // On the Markdown of both notes
+    pub model: String,
+    pub created_at: String,
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct FollowUpDraft {
+    pub subject: String,
+    pub body: String, // Plain text
+    pub model: String,
+    pub created_at: String,
+}
+
+impl ActionItem {
+    pub fn set_done(&mut self, done: bool) {
+        self.done = done;
+        self.done_at = match done {
+            true => Some(Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+            false => None,
+        };
+    }
+}
+
 
 impl Meeting {
     pub fn new(uuid: Option<Uuid>, title:String, datetime: chrono::DateTime<Utc>, audio_path: String) -> Self {
@@ -60,6 +136,16 @@ impl Meeting {
             published: false,
             publish_with_note: Some(false),
             chapters: Vec::new(),
+            detected_language: None,
+            language_confidence: None,
+            segments: None,
+            redaction: None,
+            action_items: None,
+            summary_info: None,
+            note_revision: None,
+            participants: None,
+            meeting_type: None,
+            follow_up: None,
         };
         let _ = s.save();
         return s;
@@ -130,25 +216,50 @@ impl Meeting {
                 warn!("Error while deleting meeting file {}.json: {:?}", self.uuid, error);
             }
         }
+        Conversation::delete_from_disk(&self.uuid);
+        SummaryHistory::delete_from_disk(&self.uuid);
+        cache::delete_for_meeting(&self.uuid);
+        if !self.company_id.is_empty() {
+            CompanyDigest::delete_from_disk(&self.company_id);
+        }
     }
 
     pub fn get_uuid(&self) -> String {
         return self.uuid.clone();
     }
 
-    pub fn summarize(&mut self) -> Result<(), Error>{
+    /// Prompt of the meeting with its variables replaced, and the model to use
+    /// -> Prompt of the first matching rule if the meeting has none - None if no rule matches
+    pub fn rendered_prompt(&self) -> Result<(Option<String>, Option<String>), Error> {
+        if !self.prompt.trim().is_empty() {
+            return Ok((Some(render_template(&self.prompt, self)?), None));
+        }
+        let setting = SettingController::new(crate::model::SettingPath::Default).get_setting()?;
+        match setting.matching_prompt(self) {
+            Some((prompt, model)) => {
+                info!("Meeting {} summarized with the prompt `{}` of a rule", self.uuid, prompt.name);
+                return Ok((Some(render_template(&prompt.prompt, self)?), model));
+            },
+            None => return Ok((None, None)),
+        }
+    }
+
+    pub fn preview_prompt(&self) -> Result<PromptPreview, Error> {
+        let (prompt, model) = self.rendered_prompt()?;
+        return preview_prompt(&self.transcript, &prompt, &model);
+    }
+
+    pub fn summarize(&mut self, observer: &SummaryObserver, force: bool) -> Result<(), Error>{
         if !self.transcript.is_empty() {
-            let prompt = match self.prompt.is_empty() {
-                true => None,
-                false => Some(self.prompt.clone())
-            };
-            match summarize_with_openai(self.transcript.clone(), prompt) {
-                Ok(summary) => {
-                    self.summary = summary;
+            let (prompt, model) = self.rendered_prompt()?;
+            match summarize_transcript(self.transcript.clone(), prompt.clone(), model, observer, UsageContext::new(Some(self.get_uuid()), UsageOperation::Summary), vec![self.get_uuid()], force) {
+                Ok(response) => {
+                    self.summary = markdown::ensure_markdown(&response.content);
+                    self.summary_info = Some(SummaryInfo::llm(prompt, response.model));
                 },
                 Err(error) => {
                     error!("Error while summarizing meeting {}: {:?}", self.uuid, error);
-                    return Err(anyhow::anyhow!(error));
+                    return Err(error);
                 }
             }
             info!("{}", format!("Summary generated successfully: {}", self.summary));
@@ -158,17 +269,56 @@ impl Meeting {
         }
     }
 
-    pub fn improve_note(&mut self) -> Result<(), Error> {
+    /// Replace the open items by a new extraction - Items already done are kept
+    pub fn set_extracted_action_items(&mut self, extracted: Vec<ExtractedActionItem>) {
+        let mut items: Vec<ActionItem> = self.action_items.clone().unwrap_or_default().into_iter()
+            .filter(|item| item.done)
+            .collect();
+        for item in extracted {
+            if items.iter().any(|known| known.task.to_lowercase() == item.task.to_lowercase()) {
+                continue;
+            }
+            items.push(ActionItem {
+                uuid: Uuid::new_v4().to_string(),
+                task: item.task,
+                owner: item.owner,
+                due_date: item.due_date,
+                source_quote: item.source_quote,
+                done: false,
+                done_at: None,
+            });
+        }
+        self.action_items = Some(items);
+    }
+
+    pub fn set_action_item_done(&mut self, item_id: &String, done: bool) -> Result<ActionItem, Error> {
+        let item = self.action_items.as_mut()
+            .and_then(|items| items.iter_mut().find(|item| &item.uuid == item_id))
+            .ok_or(anyhow::anyhow!("Action item does not exist"))?;
+        item.set_done(done);
+        return Ok(item.clone());
+    }
+
+    /// The refined note is kept as a revision of the note, the summary is left untouched
+    pub fn improve_note(&mut self, observer: &SummaryObserver, force: bool) -> Result<(), Error> {
         if !self.note.is_empty() {
-            let prompt = format!("Refine and complete the note with missing information, maintaining a similar structure in HTML format. This is crucial for accurate documentation. <note>{}</note>", self.note);
-            match summarize_with_openai(self.transcript.clone(), Some(prompt)) {
-                Ok(note) => {
-                    self.summary = note;
+            let prompt = format!("Refine and complete the note with missing information, maintaining a similar structure. This is crucial for accurate documentation. <note>{}</note>", markdown::from_html(&self.note));
+            match summarize_transcript(self.transcript.clone(), Some(prompt), None, observer, UsageContext::new(Some(self.get_uuid()), UsageOperation::ImproveNote), vec![self.get_uuid()], force) {
+                Ok(response) => {
+                    // Notes are HTML, the diff is on Markdown so both sides are normalized the same way
+                    let proposed = markdown::ensure_markdown(&response.content);
+                    self.note_revision = Some(NoteRevision {
+                        original: self.note.clone(),
+                        diff: diff_lines(&markdown::from_html(&self.note), &proposed),
+                        proposed: markdown::to_html(&proposed),
+                        model: response.model,
+                        created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                    });
                     return Ok(());
                 },
                 Err(error) => {
                     error!("Error while improving note of meeting {}: {:?}", self.uuid, error);
-                    return Err(anyhow::anyhow!(error));
+                    return Err(error);
                 }
             }
         } else {
@@ -176,6 +326,52 @@ impl Meeting {
         }
     }
 
+    /// Only fills what the user did not set - New meetings are titled with their starting time
+    pub fn apply_metadata(&mut self, metadata: &MeetingMetadata, setting: &Setting) {
+        let placeholder_title = self.title.trim().is_empty() || chrono::DateTime::parse_from_rfc2822(&self.title).is_ok();
+        if let Some(title) = metadata.title.clone().filter(|_| placeholder_title) {
+            self.title = title;
+        }
+        if self.company_name.trim().is_empty() && self.company_id.trim().is_empty() {
+            if let Some(company_name) = metadata.company_name.clone() {
+                self.company_name = company_name;
+                self.company_id = metadata.company_id.clone().unwrap_or_default();
+            }
+        }
+        if metadata.meeting_type.is_some() {
+            self.meeting_type = metadata.meeting_type.clone();
+        }
+        if !metadata.participants.is_empty() {
+            self.participants = Some(metadata.participants.clone());
+        }
+        // A matching prompt rule is preferred to the suggestion
+        if self.prompt.trim().is_empty() && setting.matching_prompt(self).is_none() {
+            let prompts = setting.prompts.clone().unwrap_or_default();
+            if let Some(prompt) = metadata.suggested_prompt.as_ref().and_then(|name| prompts.iter().find(|prompt| &prompt.name == name)) {
+                self.prompt = prompt.prompt.clone();
+            }
+        }
+    }
+
+    /// `lines` are the indexes of the changed diff lines to apply - None applies every change
+    pub fn accept_note_revision(&mut self, lines: Option<Vec<usize>>) -> Result<(), Error> {
+        let revision = self.note_revision.take().ok_or(anyhow::anyhow!("No note revision to accept"))?;
+        if revision.original != self.note {
+            self.note_revision = Some(revision);
+            return Err(anyhow::anyhow!("The note changed since the revision was proposed - Reject it and improve the note again"));
+        }
+        self.note = match lines {
+            Some(lines) => markdown::to_html(&apply_diff(&revision.diff, &lines)),
+            None => revision.proposed,
+        };
+        return Ok(());
+    }
+
+    pub fn reject_note_revision(&mut self) -> Result<(), Error> {
+        self.note_revision.take().ok_or(anyhow::anyhow!("No note revision to reject"))?;
+        return Ok(());
+    }
+
 }
 
 #[skip_serializing_none]
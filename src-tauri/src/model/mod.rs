@@ -1,8 +1,20 @@
 // Model
+mod company_digest;
+mod conversation;
 mod meeting;
+mod prompt_library;
 mod setting;
+mod summary_history;
+mod transcription_job;
+mod usage;
+pub use company_digest::*;
+pub use conversation::*;
 pub use meeting::*;
+pub use prompt_library::{PromptImportReport, PromptPackFormat};
 pub use setting::*;
+pub use summary_history::*;
+pub use transcription_job::*;
+pub use usage::*;
 
 // Controller
 mod controller;
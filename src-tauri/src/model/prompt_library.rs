@@ -0,0 +1,223 @@
+/*
+    * Prompt library
+    * Prompts are shared between users as packs (JSON or YAML files)
+    * An imported prompt with the id of a local prompt replaces it only if its version is newer
+    * Prompts without id are matched by name
+*/
+use std::path::Path;
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use crate::model::Prompt;
+
+#[derive(Clone, Debug, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum PromptPackFormat {
+    Json,
+    Yaml,
+}
+
+impl PromptPackFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PromptPackFormat::Json => "json",
+            PromptPackFormat::Yaml => "yaml",
+        }
+    }
+
+    /// YAML for `.yaml` & `.yml` files - JSON otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()).map(|extension| extension.to_lowercase()).as_deref() {
+            Some("yaml") | Some("yml") => PromptPackFormat::Yaml,
+            _ => PromptPackFormat::Json,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct PromptPack {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub prompts: Vec<Prompt>,
+}
+
+/// Names of the imported prompts, by what was done with them
+#[derive(Clone, Default, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct PromptImportReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub skipped: Vec<String>, // The local prompt is as recent
+    pub renamed: Vec<String>, // `name -> new name` - Another prompt has the name
+}
+
+pub fn serialize(pack: &PromptPack, format: &PromptPackFormat) -> Result<String, Error> {
+    match format {
+        PromptPackFormat::Json => return Ok(serde_json::to_string_pretty(pack)?),
+        PromptPackFormat::Yaml => return Ok(serde_yaml::to_string(pack)?),
+    }
+}
+
+pub fn deserialize(content: &str, format: &PromptPackFormat) -> Result<PromptPack, Error> {
+    let pack: PromptPack = match format {
+        PromptPackFormat::Json => serde_json::from_str(content)?,
+        PromptPackFormat::Yaml => serde_yaml::from_str(content)?,
+    };
+    if let Some(prompt) = pack.prompts.iter().find(|prompt| prompt.name.trim().is_empty() || prompt.prompt.trim().is_empty()) {
+        return Err(anyhow::anyhow!("Invalid prompt pack - Every prompt needs a name and a text (`{}`)", prompt.name));
+    }
+    return Ok(pack);
+}
+
+/// Prompts saved before the library existed have no id
+pub fn assign_ids(prompts: &mut Vec<Prompt>) {
+    for prompt in prompts.iter_mut() {
+        if prompt.id.as_ref().map(|id| id.trim().is_empty()).unwrap_or(true) {
+            prompt.id = Some(Uuid::new_v4().to_string());
+        }
+        if prompt.version.is_none() {
+            prompt.version = Some(1);
+        }
+    }
+}
+
+/// A prompt whose text changed is a new version
+pub fn bump_versions(prompts: &mut Vec<Prompt>, previous: &Vec<Prompt>) {
+    for prompt in prompts.iter_mut() {
+        let known = previous.iter().find(|known| known.id.is_some() && known.id == prompt.id);
+        if let Some(known) = known {
+            if known.prompt != prompt.prompt && prompt.version <= known.version {
+                prompt.version = Some(known.version.unwrap_or(1) + 1);
+            }
+        }
+    }
+}
+
+fn unique_name(prompts: &Vec<Prompt>, name: &str) -> String {
+    let taken = |candidate: &str| prompts.iter().any(|known| known.name.to_lowercase() == candidate.to_lowercase());
+    let mut candidate = format!("{} (imported)", name);
+    let mut index = 2;
+    while taken(&candidate) {
+        candidate = format!("{} (imported {})", name, index);
+        index += 1;
+    }
+    return candidate;
+}
+
+/// Same id - The name is only used for prompts without id
+/// -> A prompt with another id but a known name is a different prompt, added under a new name
+pub fn merge(prompts: &mut Vec<Prompt>, imported: Vec<Prompt>) -> PromptImportReport {
+    let mut report = PromptImportReport::default();
+    for mut prompt in imported {
+        let has_id = prompt.id.as_ref().map(|id| !id.trim().is_empty()).unwrap_or(false);
+        let position = match has_id {
+            true => prompts.iter().position(|known| known.id == prompt.id),
+            false => prompts.iter().position(|known| known.name.to_lowercase() == prompt.name.to_lowercase()),
+        };
+        match position {
+            Some(position) => {
+                let known = &mut prompts[position];
+                if prompt.version.unwrap_or(1) > known.version.unwrap_or(1) {
+                    known.prompt = prompt.prompt;
+                    known.version = prompt.version;
+                    known.description = prompt.description.or(known.description.clone());
+                    report.updated.push(known.name.clone());
+                } else {
+                    report.skipped.push(prompt.name);
+                }
+            },
+            None => {
+                if prompts.iter().any(|known| known.name.to_lowercase() == prompt.name.to_lowercase()) {
+                    let name = unique_name(prompts, &prompt.name);
+                    report.renamed.push(format!("{} -> {}", prompt.name, name));
+                    prompt.name = name;
+                }
+                report.added.push(prompt.name.clone());
+                prompts.push(prompt);
+            }
+        }
+    }
+    assign_ids(prompts);
+    return report;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(id: Option<&str>, name: &str, text: &str, version: u32) -> Prompt {
+        return Prompt {
+            id: id.map(|id| id.to_string()),
+            name: name.to_string(),
+            prompt: text.to_string(),
+            version: Some(version),
+            description: None,
+        };
+    }
+
+    #[test]
+    fn updates_same_id_with_newer_version_only() {
+        let mut prompts = vec![prompt(Some("1"), "Summary", "old", 1)];
+        let report = merge(&mut prompts, vec![prompt(Some("1"), "Summary v2", "new", 2)]);
+        assert_eq!(report.updated, vec!["Summary"]);
+        assert_eq!(prompts[0].prompt, "new");
+        assert_eq!(prompts[0].name, "Summary");
+        assert_eq!(prompts[0].version, Some(2));
+
+        let report = merge(&mut prompts, vec![prompt(Some("1"), "Summary", "older", 1)]);
+        assert_eq!(report.skipped, vec!["Summary"]);
+        assert_eq!(prompts[0].prompt, "new");
+    }
+
+    #[test]
+    fn keeps_local_prompt_with_the_same_name_and_another_id() {
+        let mut prompts = vec![prompt(Some("1"), "Summary", "local", 1)];
+        let report = merge(&mut prompts, vec![prompt(Some("2"), "summary", "imported", 5)]);
+        assert_eq!(prompts.len(), 2);
+        assert_eq!(prompts[0].id, Some("1".to_string()));
+        assert_eq!(prompts[0].prompt, "local");
+        assert_eq!(prompts[1].id, Some("2".to_string()));
+        assert_eq!(prompts[1].name, "summary (imported)");
+        assert_eq!(report.added, vec!["summary (imported)"]);
+        assert_eq!(report.renamed.len(), 1);
+    }
+
+    #[test]
+    fn matches_prompts_without_id_by_name() {
+        let mut prompts = vec![prompt(Some("1"), "Summary", "local", 1)];
+        let report = merge(&mut prompts, vec![prompt(None, "SUMMARY", "imported", 2)]);
+        assert_eq!(report.updated, vec!["Summary"]);
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].id, Some("1".to_string()));
+        assert_eq!(prompts[0].prompt, "imported");
+    }
+
+    #[test]
+    fn adds_new_prompts_with_an_id() {
+        let mut prompts = Vec::new();
+        let report = merge(&mut prompts, vec![prompt(None, "Follow-up", "text", 1)]);
+        assert_eq!(report.added, vec!["Follow-up"]);
+        assert!(prompts[0].id.is_some());
+    }
+
+    #[test]
+    fn bumps_the_version_of_changed_prompts() {
+        let previous = vec![prompt(Some("1"), "A", "text", 3), prompt(Some("2"), "B", "text", 1)];
+        let mut prompts = vec![prompt(Some("1"), "A", "changed", 3), prompt(Some("2"), "B", "text", 1)];
+        bump_versions(&mut prompts, &previous);
+        assert_eq!(prompts[0].version, Some(4));
+        assert_eq!(prompts[1].version, Some(1));
+    }
+
+    #[test]
+    fn reads_back_both_formats() {
+        let pack = PromptPack { name: Some("Team".to_string()), description: None, prompts: vec![prompt(Some("1"), "A", "text", 2)] };
+        for format in [PromptPackFormat::Json, PromptPackFormat::Yaml] {
+            let read = deserialize(&serialize(&pack, &format).unwrap(), &format).unwrap();
+            assert_eq!(read.prompts[0].id, Some("1".to_string()));
+            assert_eq!(read.prompts[0].version, Some(2));
+        }
+        assert!(deserialize(r#"{"name": null, "description": null, "prompts": [{"id": null, "name": "", "prompt": "x", "version": null, "description": null}]}"#, &PromptPackFormat::Json).is_err());
+    }
+}
@@ -1,17 +1,167 @@
 use std::{fs::File, io::Write, path::Path, process::Command};
-use crate::summarizer::openai;
+use crate::summarizer::{openai, template};
+use crate::transcriber::correction;
+use crate::model::Meeting;
+use regex::RegexBuilder;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use anyhow::Error;
 use uuid::Uuid;
 use log::{info, warn};
-use crate::utils::filesys::local_data_dir_path;
+use crate::utils::filesys::{local_data_dir_path, reveal_in_file_manager};
+use crate::model::prompt_library::{self, PromptImportReport, PromptPack, PromptPackFormat};
 
 #[derive(Clone, Deserialize, Serialize, TS)]
 #[ts(export, export_to = "../src/bindings/")]
 pub struct Prompt {
+    pub id: Option<String>, // Same prompt across the libraries of a team
     pub name: String,
     pub prompt: String,
+    pub version: Option<u32>,
+    pub description: Option<String>,
+}
+
+impl Prompt {
+    pub fn new(name: &str, prompt: &str) -> Self {
+        Self {
+            id: Some(Uuid::new_v4().to_string()),
+            name: name.to_string(),
+            prompt: prompt.to_string(),
+            version: Some(1),
+            description: None,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct CorrectionRule {
+    pub find: String,
+    pub replace: String,
+    pub is_regex: bool,
+    pub case_sensitive: bool,
+    pub company_id: Option<String>, // Only apply to the meetings of this company
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum LlmProviderKind {
+    OpenAI,
+    AzureOpenAI,
+    OpenAICompatible, // Ollama, vLLM, company gateway, ...
+    Anthropic,
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct LlmSetting {
+    pub provider: LlmProviderKind,
+    pub model: String, // Deployment name for Azure OpenAI
+    pub base_url: Option<String>,
+    pub api_key: Option<String>, // Fallback on `openai_api_token` for OpenAI
+    pub api_version: Option<String>, // Azure OpenAI only
+    pub context_window: Option<u32>, // In tokens - Guessed from the model name if not set
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum EmbeddingSource {
+    Local,    // Small model run on the CPU, downloaded once in the data folder
+    Provider, // Embeddings endpoint of the configured LLM provider
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct EmbeddingSetting {
+    pub source: EmbeddingSource,
+    pub model: Option<String>, // Default model of the source if not set
+}
+
+#[derive(Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct UsageBudget {
+    pub monthly_llm_cap: Option<f64>, // USD - No cap if not set
+    pub monthly_transcription_cap: Option<f64>,
+}
+
+/// Default prompt & model of the meetings matching every condition set - The first matching rule is used
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct PromptRule {
+    pub company_id: Option<String>,
+    pub title_pattern: Option<String>, // Case insensitive regex
+    pub meeting_type: Option<String>, // Detected with the meeting metadata
+    pub prompt: String, // Name of a prompt of the settings
+    pub model: Option<String>, // Model of the settings if not set
+}
+
+impl PromptRule {
+    fn conditions(&self) -> (Option<&String>, Option<&String>, Option<&String>) {
+        fn set(value: &Option<String>) -> Option<&String> {
+            return value.as_ref().filter(|value| !value.trim().is_empty());
+        }
+        return (set(&self.company_id), set(&self.title_pattern), set(&self.meeting_type));
+    }
+
+    pub fn matches(&self, meeting: &Meeting) -> bool {
+        let (company_id, title_pattern, meeting_type) = self.conditions();
+        if company_id.is_none() && title_pattern.is_none() && meeting_type.is_none() {
+            return false;
+        }
+        if company_id.map(|company_id| company_id != &meeting.company_id).unwrap_or(false) {
+            return false;
+        }
+        if let Some(title_pattern) = title_pattern {
+            let matched = RegexBuilder::new(title_pattern).case_insensitive(true).build()
+                .map(|regex| regex.is_match(&meeting.title))
+                .unwrap_or(false);
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(meeting_type) = meeting_type {
+            if meeting.meeting_type.as_ref().map(|detected| detected.to_lowercase()) != Some(meeting_type.to_lowercase()) {
+                return false;
+            }
+        }
+        return true;
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct DigestSchedule {
+    pub enabled: bool,
+    pub weekday: u8, // 0 is Monday
+    pub hour: u8,    // Local time
+    pub period_days: u32, // Days covered by the report, ending the day before it runs
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum RedactionMode {
+    Provider, // Done by AssemblyAI during the transcription
+    Local,    // Done by Watson once the transcript is received
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum PiiEntityType {
+    EmailAddress,
+    PhoneNumber,
+    BankingInformation,
+    CreditCardNumber,
+    PersonName,
+    Location,
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct RedactionPolicy {
+    pub enabled: bool,
+    pub mode: RedactionMode,
+    pub entity_types: Vec<PiiEntityType>,
+    pub redact_audio: bool,
 }
 
 
@@ -24,7 +174,17 @@ pub struct Setting {
     pub affinity_api_token: String,
     pub affinity_crm_list_id: Option<String>,
     pub prompts: Option<Vec<Prompt>>,
-    pub default_model: Option<openai::ModelTurbo>,
+    pub default_model: Option<openai::ModelTurbo>, // Used when `llm` is not set
+    pub llm: Option<LlmSetting>,
+    pub glossary: Option<Vec<String>>, // Names and jargon boosted during transcription
+    pub correction_rules: Option<Vec<CorrectionRule>>, // Applied to the transcript before saving it
+    pub redaction: Option<RedactionPolicy>,
+    pub embedding: Option<EmbeddingSetting>, // Local model if not set
+    pub budget: Option<UsageBudget>,
+    pub auto_metadata: Option<bool>, // Title, company & prompt generated after the transcription
+    pub follow_up_prompt: Option<String>, // Default email prompt if not set
+    pub digest_schedule: Option<DigestSchedule>,
+    pub prompt_rules: Option<Vec<PromptRule>>,
 }
 
 impl Setting {
@@ -36,11 +196,46 @@ impl Setting {
             affinity_api_token: String::new(),
             affinity_crm_list_id: None,
             prompts: Some(vec![
-                Prompt { name: String::from("VC Intro Call"), prompt: String::from("Extract key details from your introductory call, ensuring accuracy and conciseness. Ignore the information about our fund, focus on the startup:\n- General discussion points\n- Team overview\n- Company's core activities\n- Problem addressed by the company\n- Target market and sales approach\n- Competitive advantages\n- Business model overview\n- Current status of the company\n- Funding status or recent fundraising efforts\n- Action items, if assigned")},
-                Prompt { name: String::from("Q&A Call"), prompt: String::from("Extract and organize questions and answers from the call in a structured format. Be accurate and use 'N/A' if information is not applicable or unknown.")}
+                Prompt::new("VC Intro Call", "Extract key details from your introductory call, ensuring accuracy and conciseness. Ignore the information about our fund, focus on the startup:\n- General discussion points\n- Team overview\n- Company's core activities\n- Problem addressed by the company\n- Target market and sales approach\n- Competitive advantages\n- Business model overview\n- Current status of the company\n- Funding status or recent fundraising efforts\n- Action items, if assigned"),
+                Prompt::new("Q&A Call", "Extract and organize questions and answers from the call in a structured format. Be accurate and use 'N/A' if information is not applicable or unknown.")
                 ]),
             default_model: Some(openai::ModelTurbo::GPT4oMini),
+            llm: None,
+            glossary: Some(Vec::new()),
+            correction_rules: Some(Vec::new()),
+            redaction: None,
+            embedding: None,
+            budget: None,
+            auto_metadata: Some(false),
+            follow_up_prompt: None,
+            digest_schedule: None,
+            prompt_rules: Some(Vec::new()),
+        }
+    }
+
+    /// Prompt & model of the first rule matching the meeting
+    pub fn matching_prompt(&self, meeting: &Meeting) -> Option<(Prompt, Option<String>)> {
+        let prompts = self.prompts.clone().unwrap_or_default();
+        for rule in self.prompt_rules.clone().unwrap_or_default().iter().filter(|rule| rule.matches(meeting)) {
+            match prompts.iter().find(|prompt| prompt.name == rule.prompt) {
+                Some(prompt) => return Some((prompt.clone(), rule.model.clone().filter(|model| !model.trim().is_empty()))),
+                None => warn!("Prompt rule skipped - No prompt named `{}`", rule.prompt),
+            }
+        }
+        return None;
+    }
+
+    /// Types of meeting the rules are based on
+    pub fn meeting_types(&self) -> Vec<String> {
+        let mut types: Vec<String> = Vec::new();
+        for rule in self.prompt_rules.clone().unwrap_or_default() {
+            if let Some(meeting_type) = rule.meeting_type.filter(|meeting_type| !meeting_type.trim().is_empty()) {
+                if !types.contains(&meeting_type) {
+                    types.push(meeting_type);
+                }
+            }
         }
+        return types;
     }
 }
 
@@ -92,7 +287,15 @@ impl SettingController {
                     // Alway provide the default prompts if the file doesn't contain any.
                     settings.prompts = prompts;
                 }
+                let missing_ids = settings.prompts.iter().flatten().any(|prompt| prompt.id.is_none());
+                if let Some(prompts) = settings.prompts.as_mut() {
+                    prompt_library::assign_ids(prompts);
+                }
                 self.settings = settings;
+                // Ids must not change from one start to the next
+                if missing_ids {
+                    let _ = self.save();
+                }
             },
             Err(error) => {
                 warn!("No local data detected - Error while loading file: {:?}\n", error);
@@ -105,11 +308,84 @@ impl SettingController {
     }
 
     pub fn set_setting(&mut self, setting: Setting) -> Result<(), Error> {
+        let mut setting = setting;
+        if let Some(prompts) = setting.prompts.as_mut() {
+            prompt_library::bump_versions(prompts, &self.settings.prompts.clone().unwrap_or_default());
+            prompt_library::assign_ids(prompts);
+        }
+        correction::validate_rules(&setting.correction_rules.clone().unwrap_or_default())?;
+        for prompt in setting.prompts.clone().unwrap_or_default().iter() {
+            if let Err(error) = template::validate_template(&prompt.prompt) {
+                return Err(anyhow::anyhow!("Prompt `{}`: {}", prompt.name, error));
+            }
+        }
+        if let Err(error) = template::validate_template(&setting.follow_up_prompt.clone().unwrap_or_default()) {
+            return Err(anyhow::anyhow!("Follow-up email prompt: {}", error));
+        }
+        let prompts = setting.prompts.clone().unwrap_or_default();
+        for (index, rule) in setting.prompt_rules.clone().unwrap_or_default().iter().enumerate() {
+            let (company_id, title_pattern, meeting_type) = rule.conditions();
+            if company_id.is_none() && title_pattern.is_none() && meeting_type.is_none() {
+                return Err(anyhow::anyhow!("Prompt rule {}: a company, a title pattern or a meeting type is needed", index + 1));
+            }
+            if let Some(Err(error)) = title_pattern.map(|title_pattern| RegexBuilder::new(title_pattern).build()) {
+                return Err(anyhow::anyhow!("Prompt rule {}: invalid title pattern: {}", index + 1, error));
+            }
+            if !prompts.iter().any(|prompt| prompt.name == rule.prompt) {
+                return Err(anyhow::anyhow!("Prompt rule {}: no prompt named `{}`", index + 1, rule.prompt));
+            }
+        }
+        if let Some(schedule) = &setting.digest_schedule {
+            if schedule.weekday > 6 || schedule.hour > 23 || schedule.period_days == 0 {
+                return Err(anyhow::anyhow!("Digest schedule: the weekday must be 0 to 6, the hour 0 to 23 and the period at least 1 day"));
+            }
+        }
         self.settings = setting;
         let _ = self.save();
         return Ok(());
     }
 
+    /// Every prompt if `names` is not set -> Return the path of the file written in the export folder
+    pub fn export_prompts(&self, format: PromptPackFormat, names: Option<Vec<String>>) -> Result<String, Error> {
+        let prompts: Vec<Prompt> = self.settings.prompts.clone().unwrap_or_default().into_iter()
+            .filter(|prompt| names.as_ref().map(|names| names.contains(&prompt.name)).unwrap_or(true))
+            .collect();
+        if prompts.is_empty() {
+            return Err(anyhow::anyhow!("No prompt to export"));
+        }
+        let pack = PromptPack { name: None, description: None, prompts };
+        let content = prompt_library::serialize(&pack, &format)?;
+        let file_name = format!("prompts_{}.{}", chrono::Local::now().format("%Y-%m-%d"), format.extension());
+        let file_path = local_data_dir_path().join("export").join(file_name);
+        let parent_dir = file_path.parent().unwrap();
+        if !parent_dir.exists() {std::fs::create_dir_all(parent_dir)?;}
+        let mut file = File::create(&file_path)?;
+        file.write_all(content.as_bytes())?;
+        info!("Prompts exported to {:?}", file_path);
+
+        reveal_in_file_manager(&file_path);
+
+        return Ok(file_path.to_str().unwrap().to_string());
+    }
+
+    /// JSON or YAML pack, guessed from the extension
+    pub fn import_prompts(&mut self, path: String) -> Result<PromptImportReport, Error> {
+        let path = Path::new(&path);
+        let content = std::fs::read_to_string(path)?;
+        let pack = prompt_library::deserialize(&content, &PromptPackFormat::from_path(path))?;
+        for prompt in pack.prompts.iter() {
+            if let Err(error) = template::validate_template(&prompt.prompt) {
+                return Err(anyhow::anyhow!("Prompt `{}`: {}", prompt.name, error));
+            }
+        }
+        let mut prompts = self.settings.prompts.clone().unwrap_or_default();
+        let report = prompt_library::merge(&mut prompts, pack.prompts);
+        self.settings.prompts = Some(prompts);
+        self.save()?;
+        info!("Prompts imported from {:?} - {} added, {} updated, {} skipped", path, report.added.len(), report.updated.len(), report.skipped.len());
+        return Ok(report);
+    }
+
     pub fn open_data_folder(&self) -> Result<(), Error> {
         let path = Path::new(&self.local_data_path);
         // Open the folder containing the zip file for the user to find it
@@ -0,0 +1,131 @@
+/*
+    * Summary History Model
+    * Previous versions of the summary of a meeting, saved each time the summary is replaced
+    * Kept out of the meeting file so a meeting sent back by the UI can't overwrite the history
+*/
+use std::{fs::File, io::Read, io::Write};
+use std::path::{Path, PathBuf};
+use anyhow::Error;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use crate::utils::filesys::local_data_dir_path;
+use log::{info, warn, error};
+
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum SummaryOrigin {
+    Llm,
+    User,
+}
+
+/// Who wrote the current summary, and how
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct SummaryInfo {
+    pub origin: SummaryOrigin,
+    pub prompt: Option<String>,
+    pub model: Option<String>,
+    pub created_at: String,
+}
+
+impl SummaryInfo {
+    pub fn llm(prompt: Option<String>, model: String) -> Self {
+        Self {
+            origin: SummaryOrigin::Llm,
+            prompt,
+            model: Some(model),
+            created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        }
+    }
+
+    pub fn user() -> Self {
+        Self {
+            origin: SummaryOrigin::User,
+            prompt: None,
+            model: None,
+            created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct SummaryVersion {
+    pub uuid: String,
+    pub summary: String,
+    pub info: Option<SummaryInfo>, // Unknown for summaries written before the history existed
+    pub replaced_at: String,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SummaryHistory {
+    pub meeting_id: String,
+    pub versions: Vec<SummaryVersion>, // Oldest first
+}
+
+impl SummaryHistory {
+    fn path(meeting_id: &String) -> PathBuf {
+        return local_data_dir_path().join("summaryHistory").join(format!("{}.json", meeting_id));
+    }
+
+    /// A meeting without history file has no previous version
+    pub fn load(meeting_id: String) -> Result<Self, Error> {
+        let file = File::open(Self::path(&meeting_id));
+        match file {
+            Ok(mut file) => {
+                let mut contents = String::new();
+                let _ = file.read_to_string(&mut contents);
+                match serde_json::from_str(&contents) {
+                    Ok(history) => Ok(history),
+                    Err(error) => {
+                        error!("Error while parsing summary history {}.json: {:?}\n", meeting_id, error);
+                        Err(error.into())
+                    }
+                }
+            },
+            Err(_) => Ok(Self { meeting_id, versions: Vec::new() }),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let history_path = Self::path(&self.meeting_id);
+        let parent_dir = Path::new(&history_path).parent().unwrap();
+        if !parent_dir.exists() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
+        let mut file = File::create(&history_path)?;
+        let serialized = serde_json::to_string(&self)?;
+        file.write_all(serialized.as_bytes())?;
+        return Ok(());
+    }
+
+    pub fn push(&mut self, summary: String, info: Option<SummaryInfo>) {
+        self.versions.push(SummaryVersion {
+            uuid: Uuid::new_v4().to_string(),
+            summary,
+            info,
+            replaced_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        });
+    }
+
+    pub fn get(&self, version_id: &String) -> Result<SummaryVersion, Error> {
+        match self.versions.iter().find(|version| &version.uuid == version_id) {
+            Some(version) => Ok(version.clone()),
+            None => Err(anyhow::anyhow!("Summary version does not exist")),
+        }
+    }
+
+    pub fn delete_from_disk(meeting_id: &String) {
+        let history_path = Self::path(meeting_id);
+        if !history_path.exists() {
+            return;
+        }
+        match std::fs::remove_file(history_path) {
+            Ok(_) => info!("Summary history {}.json deleted successfully", meeting_id),
+            Err(error) => warn!("Error while deleting summary history {}.json: {:?}", meeting_id, error),
+        }
+    }
+}
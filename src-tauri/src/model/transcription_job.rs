@@ -0,0 +1,71 @@
+/*
+    * Transcription Job Model
+    * Keep track of a transcription sent to a provider, so it can be resumed if the app is closed
+*/
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use crate::model::RedactionPolicy;
+
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum TranscriptionStatus {
+    Uploading,  // Audio not yet accepted by the provider - Must restart from scratch
+    Processing, // Provider job id is known - Only polling is needed
+    Completed,
+    Cancelled,
+    Error,
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct TranscriptionJob {
+    uuid: String,
+    pub meeting_id: String,
+    pub audio_path: String,
+    pub language: Option<String>,
+    pub provider: String,
+    pub provider_job_id: Option<String>,
+    pub status: TranscriptionStatus,
+    pub error: Option<String>,
+    pub redaction: Option<RedactionPolicy>, // Policy in use when the audio was submitted
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl TranscriptionJob {
+    pub fn new(meeting_id: String, audio_path: String, language: Option<String>) -> Self {
+        let now = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        Self {
+            uuid: Uuid::new_v4().to_string(),
+            meeting_id,
+            audio_path,
+            language,
+            provider: String::from("assemblyai"),
+            provider_job_id: None,
+            status: TranscriptionStatus::Uploading,
+            error: None,
+            redaction: None,
+            created_at: now.clone(),
+            updated_at: now,
+        }
+    }
+
+    pub fn get_uuid(&self) -> String {
+        return self.uuid.clone();
+    }
+
+    pub fn is_pending(&self) -> bool {
+        match self.status {
+            TranscriptionStatus::Uploading | TranscriptionStatus::Processing => true,
+            _ => false,
+        }
+    }
+
+    pub fn set_status(&mut self, status: TranscriptionStatus) {
+        self.status = status;
+        self.updated_at = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    }
+}
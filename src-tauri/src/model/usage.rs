@@ -0,0 +1,135 @@
+/*
+    * Usage Model
+    * One entry per request billed by a provider - Costs are estimated from public list prices
+*/
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+// USD per million tokens (prompt, completion) - First matching prefix wins
+const LLM_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("gpt-4-turbo", 10.00, 30.00),
+    ("gpt-4", 30.00, 60.00),
+    ("gpt-3.5", 0.50, 1.50),
+    ("o4-mini", 1.10, 4.40),
+    ("o3-mini", 1.10, 4.40),
+    ("o3", 2.00, 8.00),
+    ("o1-mini", 1.10, 4.40),
+    ("o1", 15.00, 60.00),
+    ("claude-3-haiku", 0.25, 1.25),
+    ("claude-3-5-haiku", 0.80, 4.00),
+    ("claude-haiku", 1.00, 5.00),
+    ("claude-3-opus", 15.00, 75.00),
+    ("claude-opus", 15.00, 75.00),
+    ("claude", 3.00, 15.00), // Sonnet
+    ("text-embedding-3-small", 0.02, 0.0),
+    ("text-embedding-3-large", 0.13, 0.0),
+    ("text-embedding-ada-002", 0.10, 0.0),
+];
+// USD per hour of audio
+const ASSEMBLYAI_PRICE_PER_HOUR: f64 = 0.37;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum UsageKind {
+    Llm,
+    Transcription,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum UsageOperation {
+    Summary,
+    ImproveNote,
+    Metadata,
+    FollowUp,
+    CompanyDigest,
+    ActionItems,
+    Chat,
+    Embedding,
+    Transcription,
+}
+
+/// What a request is made for
+#[derive(Clone, Debug)]
+pub struct UsageContext {
+    pub meeting_id: Option<String>,
+    pub operation: UsageOperation,
+}
+
+impl UsageContext {
+    pub fn new(meeting_id: Option<String>, operation: UsageOperation) -> Self {
+        Self { meeting_id, operation }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct UsageEntry {
+    pub uuid: String,
+    pub meeting_id: Option<String>,
+    pub kind: UsageKind,
+    pub operation: UsageOperation,
+    pub provider: String,
+    pub model: String,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub audio_seconds: f64,
+    pub estimated_cost: f64, // USD
+    pub created_at: String,
+}
+
+/// Local & unknown models are free
+pub fn estimate_llm_cost(model: &str, prompt_tokens: u32, completion_tokens: u32) -> f64 {
+    let model = model.to_lowercase();
+    match LLM_PRICES.iter().find(|(prefix, _, _)| model.starts_with(prefix)) {
+        Some((_, prompt_price, completion_price)) => {
+            (prompt_tokens as f64 * prompt_price + completion_tokens as f64 * completion_price) / 1_000_000_f64
+        },
+        None => 0_f64,
+    }
+}
+
+pub fn estimate_transcription_cost(audio_seconds: f64) -> f64 {
+    return audio_seconds / 3600_f64 * ASSEMBLYAI_PRICE_PER_HOUR;
+}
+
+impl UsageEntry {
+    pub fn llm(context: &UsageContext, provider: &str, model: &str, prompt_tokens: u32, completion_tokens: u32) -> Self {
+        Self {
+            uuid: Uuid::new_v4().to_string(),
+            meeting_id: context.meeting_id.clone(),
+            kind: UsageKind::Llm,
+            operation: context.operation.clone(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            prompt_tokens,
+            completion_tokens,
+            audio_seconds: 0_f64,
+            estimated_cost: estimate_llm_cost(model, prompt_tokens, completion_tokens),
+            created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        }
+    }
+
+    pub fn transcription(meeting_id: Option<String>, provider: &str, audio_seconds: f64) -> Self {
+        Self {
+            uuid: Uuid::new_v4().to_string(),
+            meeting_id,
+            kind: UsageKind::Transcription,
+            operation: UsageOperation::Transcription,
+            provider: provider.to_string(),
+            model: String::new(),
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            audio_seconds,
+            estimated_cost: estimate_transcription_cost(audio_seconds),
+            created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        }
+    }
+}
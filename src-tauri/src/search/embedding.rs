@@ -0,0 +1,175 @@
+/*
+    * Embedding models
+    * Text is turned into vectors, close meanings give close vectors
+*/
+use std::sync::Mutex;
+use std::time::Duration;
+use anyhow::Error;
+use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+use log::info;
+use serde::Deserialize;
+use crate::model::{EmbeddingSource, LlmProviderKind, Setting, UsageContext, UsageEntry, UsageKind, UsageLedger, UsageOperation};
+use crate::summarizer::chunking;
+use crate::summarizer::provider::{llm_setting, request_error};
+use crate::utils::filesys::local_data_dir_path;
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const AZURE_API_VERSION: &str = "2024-06-01";
+const DEFAULT_PROVIDER_MODEL: &str = "text-embedding-3-small";
+const LOCAL_MODEL: &str = "all-MiniLM-L6-v2";
+// Texts sent per request
+const BATCH_SIZE: usize = 64;
+
+// Loading the local model take a few seconds - Kept for the lifetime of the app
+static LOCAL_EMBEDDING: Mutex<Option<TextEmbedding>> = Mutex::new(None);
+
+pub trait Embedder {
+    /// Vectors of different models can't be compared - Part of the index
+    fn model_id(&self) -> String;
+    fn embed(&self, texts: &Vec<String>) -> Result<Vec<Vec<f32>>, Error>;
+}
+
+pub struct LocalEmbedder;
+
+impl Embedder for LocalEmbedder {
+    fn model_id(&self) -> String {
+        return format!("local/{}", LOCAL_MODEL);
+    }
+
+    fn embed(&self, texts: &Vec<String>) -> Result<Vec<Vec<f32>>, Error> {
+        let mut model = LOCAL_EMBEDDING.lock().unwrap();
+        if model.is_none() {
+            info!("[Search] Loading local embedding model {}", LOCAL_MODEL);
+            *model = Some(TextEmbedding::try_new(InitOptions {
+                model_name: EmbeddingModel::AllMiniLML6V2,
+                cache_dir: local_data_dir_path().join("models"),
+                show_download_progress: false,
+                ..Default::default()
+            })?);
+        }
+        return model.as_ref().unwrap().embed(texts.clone(), Some(BATCH_SIZE));
+    }
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingUsage {
+    prompt_tokens: u32,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+    usage: Option<EmbeddingUsage>,
+}
+
+/// OpenAI embeddings API - Also spoken by Azure OpenAI & most compatible servers
+pub struct ProviderEmbedder {
+    agent: ureq::Agent,
+    provider: &'static str,
+    endpoint: String,
+    auth: Option<(&'static str, String)>,
+    model: String,
+}
+
+impl ProviderEmbedder {
+    pub fn from_setting(setting: &Setting, model: Option<String>) -> Result<Self, Error> {
+        let llm = llm_setting(setting);
+        let model = model.filter(|model| !model.is_empty()).unwrap_or(DEFAULT_PROVIDER_MODEL.to_string());
+        let api_key = llm.api_key.clone().filter(|api_key| !api_key.is_empty());
+        let base_url = llm.base_url.clone().filter(|url| !url.is_empty());
+        let (provider, endpoint, auth) = match llm.provider {
+            LlmProviderKind::OpenAI => (
+                "OpenAI",
+                format!("{}/embeddings", base_url.unwrap_or(OPENAI_BASE_URL.to_string()).trim_end_matches('/')),
+                Some(("Authorization", format!("Bearer {}", api_key.unwrap_or(setting.openai_api_token.clone())))),
+            ),
+            LlmProviderKind::AzureOpenAI => {
+                let base_url = base_url.ok_or(anyhow::anyhow!("Azure OpenAI needs the resource endpoint as base URL"))?;
+                let api_key = api_key.ok_or(anyhow::anyhow!("Azure OpenAI needs an API key"))?;
+                let api_version = llm.api_version.clone().filter(|version| !version.is_empty()).unwrap_or(AZURE_API_VERSION.to_string());
+                (
+                    "Azure OpenAI",
+                    format!("{}/openai/deployments/{}/embeddings?api-version={}", base_url.trim_end_matches('/'), model, api_version),
+                    Some(("api-key", api_key)),
+                )
+            },
+            LlmProviderKind::OpenAICompatible => {
+                let base_url = base_url.ok_or(anyhow::anyhow!("An OpenAI compatible server needs a base URL"))?;
+                (
+                    "OpenAI compatible",
+                    format!("{}/embeddings", base_url.trim_end_matches('/')),
+                    api_key.map(|api_key| ("Authorization", format!("Bearer {}", api_key))),
+                )
+            },
+            LlmProviderKind::Anthropic => {
+                return Err(anyhow::anyhow!("Anthropic has no embeddings API - Use the local embedding model instead"));
+            },
+        };
+        let agent = ureq::AgentBuilder::new().timeout_read(Duration::from_secs(120)).build();
+        return Ok(Self { agent, provider, endpoint, auth, model });
+    }
+}
+
+impl Embedder for ProviderEmbedder {
+    fn model_id(&self) -> String {
+        return format!("provider/{}", self.model);
+    }
+
+    /// Billed like the other LLM requests -> Counted in the monthly budget
+    fn embed(&self, texts: &Vec<String>) -> Result<Vec<Vec<f32>>, Error> {
+        let context = UsageContext::new(None, UsageOperation::Embedding);
+        let mut vectors = Vec::new();
+        for batch in texts.chunks(BATCH_SIZE) {
+            UsageLedger::check_budget(UsageKind::Llm)?;
+            let mut request = self.agent.post(&self.endpoint).set("Content-Type", "application/json");
+            if let Some((header, value)) = &self.auth {
+                request = request.set(header, value);
+            }
+            let response: EmbeddingResponse = match request.send_json(serde_json::json!({ "model": self.model, "input": batch })) {
+                Ok(response) => response.into_json()?,
+                Err(error) => return Err(request_error("Embeddings", error)),
+            };
+            // Compatible servers don't always give the usage
+            let prompt_tokens = match &response.usage {
+                Some(usage) => usage.prompt_tokens,
+                None => batch.iter().map(|text| chunking::estimate_tokens(text) as u32).sum(),
+            };
+            UsageLedger::record(UsageEntry::llm(&context, self.provider, &self.model, prompt_tokens, 0));
+            let mut data = response.data;
+            data.sort_by_key(|embedding| embedding.index);
+            if data.len() != batch.len() {
+                return Err(anyhow::anyhow!("[Embeddings] {} vectors received for {} texts", data.len(), batch.len()));
+            }
+            vectors.extend(data.into_iter().map(|embedding| embedding.embedding));
+        }
+        return Ok(vectors);
+    }
+}
+
+pub fn from_setting(setting: &Setting) -> Result<Box<dyn Embedder>, Error> {
+    match &setting.embedding {
+        Some(embedding) if embedding.source == EmbeddingSource::Provider => {
+            Ok(Box::new(ProviderEmbedder::from_setting(setting, embedding.model.clone())?))
+        },
+        _ => Ok(Box::new(LocalEmbedder)),
+    }
+}
+
+pub fn cosine_similarity(a: &Vec<f32>, b: &Vec<f32>) -> f32 {
+    if a.len() != b.len() {
+        return 0_f32;
+    }
+    let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0_f32 || norm_b == 0_f32 {
+        return 0_f32;
+    }
+    return dot / (norm_a * norm_b);
+}
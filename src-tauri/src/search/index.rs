@@ -0,0 +1,236 @@
+/*
+    * Semantic search index
+    * Passages of the transcripts, notes & summaries with their vector, saved in searchIndex.json
+    * A meeting is embedded again only when its content changed
+*/
+use std::collections::HashMap;
+use std::{fs::File, io::Read, io::Write};
+use std::path::Path;
+use anyhow::Error;
+use log::{error, info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+use crate::model::Meeting;
+use crate::summarizer::chunking;
+use crate::utils::filesys::local_data_dir_path;
+use super::embedding::{cosine_similarity, Embedder};
+
+// Short passages give precise results, the overlap keep sentences whole
+const PASSAGE_TOKENS: usize = 200;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum PassageSource {
+    Transcript,
+    Note,
+    Summary,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct IndexedPassage {
+    source: PassageSource,
+    text: String,
+    vector: Vec<f32>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct IndexedMeeting {
+    fingerprint: String,
+    title: String,
+    datetime: String,
+    passages: Vec<IndexedPassage>,
+}
+
+#[derive(Clone, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct SearchResult {
+    pub meeting_id: String,
+    pub meeting_title: String,
+    pub meeting_datetime: String,
+    pub source: PassageSource,
+    pub passage: String,
+    pub score: f32,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct SearchIndex {
+    embedding_model: String,
+    // meeting uuid -> passages
+    meetings: HashMap<String, IndexedMeeting>,
+}
+
+/// Saved in the index -> A stable hash, so a new toolchain doesn't rebuild the whole index
+fn fingerprint(meeting: &Meeting) -> String {
+    let mut hasher = Sha256::new();
+    for field in [&meeting.transcript, &meeting.note, &meeting.summary] {
+        // Length first, so moving text from a field to the next changes the hash
+        hasher.update((field.len() as u64).to_le_bytes());
+        hasher.update(field.as_bytes());
+    }
+    return format!("{:x}", hasher.finalize());
+}
+
+static TAGS: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// Notes are HTML - Summaries are Markdown, legacy ones HTML
+fn plain_text(html: &str) -> String {
+    return TAGS.replace_all(html, " ").replace("&nbsp;", " ").replace("&amp;", "&");
+}
+
+fn passages(meeting: &Meeting) -> Vec<(PassageSource, String)> {
+    let mut passages = Vec::new();
+    for (source, text) in [
+        (PassageSource::Summary, plain_text(&meeting.summary)),
+        (PassageSource::Note, plain_text(&meeting.note)),
+        (PassageSource::Transcript, meeting.transcript.clone()),
+    ] {
+        if text.trim().is_empty() {
+            continue;
+        }
+        for passage in chunking::split_into_chunks(&text, PASSAGE_TOKENS) {
+            passages.push((source.clone(), passage));
+        }
+    }
+    return passages;
+}
+
+impl SearchIndex {
+    pub fn load() -> Self {
+        let index_path = local_data_dir_path().join("searchIndex.json");
+        let file = File::open(index_path);
+        match file {
+            Ok(mut file) => {
+                let mut contents = String::new();
+                let _ = file.read_to_string(&mut contents);
+                match serde_json::from_str(&contents) {
+                    Ok(index) => {
+                        info!("Loaded searchIndex file");
+                        return index;
+                    },
+                    Err(error) => {
+                        error!("Error while parsing searchIndex file - The index will be rebuilt: {:?}\n", error);
+                        return Self::default();
+                    }
+                }
+            },
+            Err(error) => {
+                warn!("No local data - Error while loading searchIndex file: {:?}\n", error);
+                return Self::default();
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let index_path = local_data_dir_path().join("searchIndex.json");
+        let parent_dir = Path::new(&index_path).parent().unwrap();
+        if !parent_dir.exists() {
+            std::fs::create_dir_all(parent_dir)?;
+        }
+        let serialized = serde_json::to_string(&self)?;
+        let mut file = File::create(&index_path)?;
+        file.write_all(serialized.as_bytes())?;
+        return Ok(());
+    }
+
+    pub fn clear(&mut self) -> Result<(), Error> {
+        self.meetings.clear();
+        return self.save();
+    }
+
+    /// Passages of a deleted meeting must not stay on disk until the next search
+    pub fn remove(&mut self, meeting_id: &String) -> Result<(), Error> {
+        if self.meetings.remove(meeting_id).is_some() {
+            info!("[Search] Meeting {} removed from the index", meeting_id);
+            return self.save();
+        }
+        return Ok(());
+    }
+
+    /// Embed the meetings which changed since the last search, forget the deleted ones
+    /// -> Return the number of meetings embedded
+    pub fn refresh(&mut self, meeting_ids: Vec<String>, embedder: &dyn Embedder) -> Result<usize, Error> {
+        if self.embedding_model != embedder.model_id() {
+            info!("[Search] Embedding model changed to {} - Rebuilding the index", embedder.model_id());
+            self.embedding_model = embedder.model_id();
+            self.meetings.clear();
+        }
+        self.meetings.retain(|uuid, _| meeting_ids.contains(uuid));
+
+        let mut embedded = 0;
+        for uuid in meeting_ids {
+            let meeting = match Meeting::load(uuid.clone()) {
+                Ok(meeting) => meeting,
+                Err(error) => {
+                    warn!("[Search] Meeting {} not indexed: {:?}", uuid, error);
+                    continue;
+                }
+            };
+            let fingerprint = fingerprint(&meeting);
+            match self.meetings.get_mut(&uuid) {
+                // Title & date are not embedded
+                Some(indexed) if indexed.fingerprint == fingerprint => {
+                    indexed.title = meeting.title.clone();
+                    indexed.datetime = meeting.datetime.clone();
+                    continue;
+                },
+                _ => (),
+            }
+            let passages = passages(&meeting);
+            let texts: Vec<String> = passages.iter().map(|(_, text)| text.clone()).collect();
+            let vectors = match texts.is_empty() {
+                true => Vec::new(),
+                false => match embedder.embed(&texts) {
+                    Ok(vectors) => vectors,
+                    Err(error) => {
+                        // Meetings already embedded are kept
+                        self.save()?;
+                        return Err(error);
+                    }
+                },
+            };
+            self.meetings.insert(uuid, IndexedMeeting {
+                fingerprint,
+                title: meeting.title.clone(),
+                datetime: meeting.datetime.clone(),
+                passages: passages.into_iter().zip(vectors.into_iter())
+                    .map(|((source, text), vector)| IndexedPassage { source, text, vector })
+                    .collect(),
+            });
+            embedded += 1;
+        }
+        if embedded > 0 {
+            info!("[Search] {} meetings embedded", embedded);
+        }
+        self.save()?;
+        return Ok(embedded);
+    }
+
+    pub fn search(&self, query: &str, limit: usize, embedder: &dyn Embedder) -> Result<Vec<SearchResult>, Error> {
+        if query.trim().is_empty() {
+            return Err(anyhow::anyhow!("The search is empty"));
+        }
+        let query_vector = match embedder.embed(&vec![query.to_string()])?.into_iter().next() {
+            Some(vector) => vector,
+            None => return Err(anyhow::anyhow!("No vector received for the search")),
+        };
+        let mut results = Vec::new();
+        for (uuid, meeting) in self.meetings.iter() {
+            for passage in meeting.passages.iter() {
+                results.push(SearchResult {
+                    meeting_id: uuid.clone(),
+                    meeting_title: meeting.title.clone(),
+                    meeting_datetime: meeting.datetime.clone(),
+                    source: passage.source.clone(),
+                    passage: passage.text.clone(),
+                    score: cosine_similarity(&query_vector, &passage.vector),
+                });
+            }
+        }
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(limit);
+        return Ok(results);
+    }
+}
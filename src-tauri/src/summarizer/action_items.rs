@@ -0,0 +1,93 @@
+/*
+    * Action items extraction
+    * The model answer in JSON, so the items can be tracked after the meeting
+*/
+use anyhow::Error;
+use log::{info, warn};
+use serde::Deserialize;
+use crate::model::{UsageContext, UsageOperation};
+use super::chunking;
+use super::provider::{LlmMessage, LlmRequest};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ExtractedActionItem {
+    pub task: String,
+    pub owner: Option<String>,
+    pub due_date: Option<String>, // YYYY-MM-DD
+    pub source_quote: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExtractionResponse {
+    #[serde(default)]
+    action_items: Vec<ExtractedActionItem>,
+}
+
+fn build_prompt(transcript: &str, note: &str, meeting_date: &str) -> String {
+    let note = match note.trim().is_empty() {
+        true => String::new(),
+        false => format!("<note>{}</note>", note),
+    };
+    return format!(
+        "Extract the action items of the meeting: tasks someone committed to do, or was asked to do. \
+        The meeting took place on {}, use it to resolve relative dates. \
+        Answer ONLY with JSON, without markdown, in this format: \
+        {{\"action_items\": [{{\"task\": \"...\", \"owner\": \"name or null\", \"due_date\": \"YYYY-MM-DD or null\", \"source_quote\": \"exact sentence of the transcript\"}}]}} \
+        Answer {{\"action_items\": []}} if there is none. {} <transcript>{}</transcript>",
+        meeting_date, note, transcript
+    );
+}
+
+/// Models sometimes wrap the JSON in a code block or add a sentence around it
+fn parse_response(content: &str) -> Result<Vec<ExtractedActionItem>, Error> {
+    let start = content.find('{');
+    let end = content.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => return Err(anyhow::anyhow!("The model did not answer with JSON: {}", content)),
+    };
+    let response: ExtractionResponse = serde_json::from_str(json)?;
+    let items = response.action_items.into_iter()
+        .filter(|item| !item.task.trim().is_empty())
+        .map(|item| ExtractedActionItem {
+            task: item.task.trim().to_string(),
+            owner: item.owner.filter(|owner| !owner.trim().is_empty() && owner != "null"),
+            due_date: item.due_date.filter(|due_date| chrono::NaiveDate::parse_from_str(due_date, "%Y-%m-%d").is_ok()),
+            source_quote: item.source_quote.filter(|quote| !quote.trim().is_empty()),
+        })
+        .collect();
+    return Ok(items);
+}
+
+pub fn extract_action_items(meeting_id: &str, transcript: &str, note: &str, meeting_date: &str) -> Result<Vec<ExtractedActionItem>, Error> {
+    if transcript.trim().is_empty() && note.trim().is_empty() {
+        return Err(anyhow::anyhow!("No transcript or note to extract action items from"));
+    }
+    let (llm, budget) = super::configured_llm(UsageContext::new(Some(meeting_id.to_string()), UsageOperation::ActionItems))?;
+    // Long transcripts are processed part by part, the overlap can give the same item twice
+    let chunk_budget = budget.saturating_sub(chunking::estimate_tokens(&build_prompt("", note, meeting_date)) + 64) / 2;
+    let chunks = match chunking::estimate_tokens(transcript) > chunk_budget {
+        true => chunking::split_into_chunks(transcript, chunk_budget),
+        false => vec![transcript.to_string()],
+    };
+    let mut items: Vec<ExtractedActionItem> = Vec::new();
+    for chunk in chunks.iter() {
+        let request = LlmRequest::new(vec![LlmMessage::user(build_prompt(chunk, note, meeting_date))]);
+        let response = llm.complete(&request)?;
+        info!("[{}] action items received - model: {} - usage: {:?}", llm.name(), response.model, response.usage);
+        match parse_response(&response.content) {
+            Ok(extracted) => {
+                for item in extracted {
+                    if !items.iter().any(|known| known.task.to_lowercase() == item.task.to_lowercase()) {
+                        items.push(item);
+                    }
+                }
+            },
+            Err(error) => {
+                warn!("Error while parsing action items: {:?}", error);
+                return Err(error);
+            }
+        }
+    }
+    return Ok(items);
+}
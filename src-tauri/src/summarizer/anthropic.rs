@@ -0,0 +1,195 @@
+use anyhow::Error;
+use serde::Deserialize;
+use log::info;
+use crate::transcriber::CancelFlag;
+use super::provider::{read_events, request_error, LlmProvider, LlmRequest, LlmResponse, LlmRole, LlmUsage, TokenCallback};
+
+const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+// `max_tokens` is mandatory with the messages API
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+#[derive(Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MessagesUsage {
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    model: Option<String>,
+    content: Vec<ContentBlock>,
+    usage: Option<MessagesUsage>,
+}
+
+#[derive(Deserialize)]
+struct StreamMessage {
+    model: Option<String>,
+    usage: Option<StreamUsage>,
+}
+
+#[derive(Deserialize)]
+struct StreamUsage {
+    input_tokens: Option<u32>,
+    output_tokens: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct StreamDelta {
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StreamError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct StreamEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    message: Option<StreamMessage>,
+    delta: Option<StreamDelta>,
+    usage: Option<StreamUsage>,
+    error: Option<StreamError>,
+}
+
+pub struct AnthropicProvider {
+    agent: ureq::Agent,
+    endpoint: String,
+    api_key: String,
+    model: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(agent: ureq::Agent, base_url: Option<String>, api_key: String, model: String) -> Self {
+        let base_url = base_url.filter(|url| !url.is_empty()).unwrap_or(ANTHROPIC_BASE_URL.to_string());
+        Self {
+            agent,
+            endpoint: format!("{}/v1/messages", base_url.trim_end_matches('/')),
+            api_key,
+            model,
+        }
+    }
+
+    fn request(&self) -> ureq::Request {
+        return self.agent.post(&self.endpoint)
+            .set("Content-Type", "application/json")
+            .set("x-api-key", &self.api_key)
+            .set("anthropic-version", ANTHROPIC_VERSION);
+    }
+
+    fn body(&self, request: &LlmRequest) -> serde_json::Value {
+        // The system prompt is not part of the messages with Anthropic
+        let system = request.messages.iter()
+            .filter(|message| message.role == LlmRole::System)
+            .map(|message| message.content.clone())
+            .collect::<Vec<String>>()
+            .join("\n");
+        let messages: Vec<serde_json::Value> = request.messages.iter()
+            .filter(|message| message.role != LlmRole::System)
+            .map(|message| serde_json::json!({ "role": message.role.as_str(), "content": message.content }))
+            .collect();
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "max_tokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            "messages": messages,
+        });
+        if !system.is_empty() {
+            body["system"] = serde_json::json!(system);
+        }
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = serde_json::json!(temperature);
+        }
+        return body;
+    }
+}
+
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &'static str {
+        "Anthropic"
+    }
+
+    fn model(&self) -> String {
+        self.model.clone()
+    }
+
+    fn endpoint(&self) -> String {
+        self.endpoint.clone()
+    }
+
+    fn complete(&self, request: &LlmRequest) -> Result<LlmResponse, Error> {
+        info!("[Anthropic] model: {}", self.model);
+        let response = self.request().send_json(self.body(request));
+        let response: MessagesResponse = match response {
+            Ok(response) => response.into_json()?,
+            Err(error) => return Err(request_error(self.name(), error)),
+        };
+        let content = response.content.iter()
+            .filter(|block| block.kind == "text")
+            .filter_map(|block| block.text.clone())
+            .collect::<Vec<String>>()
+            .join("");
+        return Ok(LlmResponse {
+            content,
+            model: response.model.unwrap_or(self.model.clone()),
+            usage: response.usage.map(|usage| LlmUsage {
+                prompt_tokens: usage.input_tokens,
+                completion_tokens: usage.output_tokens,
+            }),
+        });
+    }
+
+    fn stream(&self, request: &LlmRequest, on_token: TokenCallback, cancel: &CancelFlag) -> Result<LlmResponse, Error> {
+        info!("[Anthropic] model: {} - streaming", self.model);
+        let mut body = self.body(request);
+        body["stream"] = serde_json::json!(true);
+        let response = match self.request().send_json(body) {
+            Ok(response) => response,
+            Err(error) => return Err(request_error(self.name(), error)),
+        };
+
+        let mut content = String::new();
+        let mut model = self.model.clone();
+        let mut usage = LlmUsage::default();
+        read_events(response, cancel, |data| {
+            let event: StreamEvent = serde_json::from_str(data)?;
+            match event.kind.as_str() {
+                "message_start" => {
+                    if let Some(message) = event.message {
+                        model = message.model.unwrap_or(model.clone());
+                        if let Some(input_tokens) = message.usage.and_then(|usage| usage.input_tokens) {
+                            usage.prompt_tokens = input_tokens;
+                        }
+                    }
+                },
+                "content_block_delta" => {
+                    if let Some(token) = event.delta.and_then(|delta| delta.text) {
+                        on_token(&token);
+                        content.push_str(&token);
+                    }
+                },
+                "message_delta" => {
+                    if let Some(output_tokens) = event.usage.and_then(|usage| usage.output_tokens) {
+                        usage.completion_tokens = output_tokens;
+                    }
+                },
+                "message_stop" => return Ok(false),
+                "error" => {
+                    let message = event.error.map(|error| error.message).unwrap_or_default();
+                    return Err(anyhow::anyhow!("[Anthropic] Generation failed: {}", message));
+                },
+                _ => {},
+            }
+            return Ok(true);
+        })?;
+        return Ok(LlmResponse { content, model, usage: Some(usage) });
+    }
+}
@@ -0,0 +1,118 @@
+/*
+    * Response cache
+    * Content-addressed: the key is the hash of everything that shapes the answer
+    * -> Asking the same thing twice returns the first answer without calling the model
+*/
+use std::{fs::File, io::Read, io::Write};
+use std::path::PathBuf;
+use anyhow::Error;
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::utils::filesys::local_data_dir_path;
+use super::provider::{LlmProvider, LlmRequest, LlmResponse};
+
+#[derive(Serialize)]
+struct CacheKey<'a> {
+    provider: &'a str,
+    endpoint: String,
+    model: String,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    budget: usize, // A smaller context window condenses the transcript differently
+    prompt: &'a str,
+}
+
+#[derive(Deserialize, Serialize)]
+struct CachedResponse {
+    content: String,
+    model: String,
+    created_at: String,
+    #[serde(default)]
+    meeting_ids: Vec<String>, // Meetings the prompt was built from
+}
+
+/// `prompt` is the prompt sent if the transcript fits in the context window - It contains the whole transcript
+pub fn key(llm: &dyn LlmProvider, request: &LlmRequest, budget: usize, prompt: &str) -> String {
+    let key = CacheKey {
+        provider: llm.name(),
+        endpoint: llm.endpoint(),
+        model: llm.model(),
+        temperature: request.temperature,
+        max_tokens: request.max_tokens,
+        budget,
+        prompt,
+    };
+    let serialized = serde_json::to_string(&key).unwrap_or_default();
+    return format!("{:x}", Sha256::digest(serialized.as_bytes()));
+}
+
+fn cache_dir() -> PathBuf {
+    return local_data_dir_path().join("responseCache");
+}
+
+fn path(key: &str) -> PathBuf {
+    return cache_dir().join(format!("{}.json", key));
+}
+
+/// No usage - Nothing was sent
+pub fn get(key: &str) -> Option<LlmResponse> {
+    let mut file = File::open(path(key)).ok()?;
+    let mut contents = String::new();
+    let _ = file.read_to_string(&mut contents);
+    match serde_json::from_str::<CachedResponse>(&contents) {
+        Ok(cached) => {
+            info!("[Summarizer] Cached response {} from {}", key, cached.created_at);
+            return Some(LlmResponse { content: cached.content, model: cached.model, usage: None });
+        },
+        Err(error) => {
+            warn!("[Summarizer] Invalid cached response {}: {:?}", key, error);
+            return None;
+        }
+    }
+}
+
+/// `meeting_ids` are the meetings the prompt was built from, so the answer is deleted with them
+pub fn put(key: &str, response: &LlmResponse, meeting_ids: &Vec<String>) -> Result<(), Error> {
+    let cache_path = path(key);
+    let parent_dir = cache_path.parent().unwrap();
+    if !parent_dir.exists() {
+        std::fs::create_dir_all(parent_dir)?;
+    }
+    let cached = CachedResponse {
+        content: response.content.clone(),
+        model: response.model.clone(),
+        created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        meeting_ids: meeting_ids.clone(),
+    };
+    let mut file = File::create(cache_path)?;
+    file.write_all(serde_json::to_string(&cached)?.as_bytes())?;
+    return Ok(());
+}
+
+/// Cached answers contain text of the transcript -> Deleted with the meeting
+/// Entries cached before the meetings were recorded can't be attributed, they are deleted too
+pub fn delete_for_meeting(meeting_id: &String) {
+    let entries = match std::fs::read_dir(cache_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let mut deleted = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let cached = std::fs::read_to_string(&path).ok()
+            .and_then(|contents| serde_json::from_str::<CachedResponse>(&contents).ok());
+        let delete = match cached {
+            Some(cached) => cached.meeting_ids.is_empty() || cached.meeting_ids.contains(meeting_id),
+            None => true,
+        };
+        if delete {
+            match std::fs::remove_file(&path) {
+                Ok(_) => deleted += 1,
+                Err(error) => warn!("[Summarizer] Error while deleting cached response {:?}: {:?}", path, error),
+            }
+        }
+    }
+    info!("[Summarizer] {} cached responses deleted with meeting {}", deleted, meeting_id);
+}
@@ -0,0 +1,120 @@
+/*
+    * Questions about a meeting
+    * The transcript, note & summary are given as context, previous questions are kept so follow-up questions work
+*/
+use anyhow::Error;
+use log::info;
+use crate::model::{ConversationMessage, ConversationRole, Meeting, UsageContext, UsageOperation};
+use super::chunking;
+use super::provider::{LlmMessage, LlmRequest, LlmRole};
+
+// Older messages are dropped from the request, they stay on disk
+const MAX_HISTORY_MESSAGES: usize = 10;
+
+const INSTRUCTION: &str = "You answer questions about a meeting, using only the meeting transcript, note and summary below. \
+If the answer is not in the meeting, say so. \
+After the answer, quote the transcript passages it relies on, each on its own line starting with `> `, copied word for word from the transcript.";
+
+fn normalize(text: &str) -> String {
+    return text.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase();
+}
+
+/// Keep the parts of the transcript sharing the most words with the question, in chronological order
+fn select_transcript(transcript: &str, question: &str, budget: usize) -> String {
+    if chunking::estimate_tokens(transcript) <= budget {
+        return transcript.to_string();
+    }
+    let keywords: Vec<String> = normalize(question).split(' ')
+        .filter(|word| word.chars().count() > 3)
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .collect();
+    let chunks = chunking::split_into_chunks(transcript, std::cmp::max(budget / 8, 256));
+    let mut scored: Vec<(usize, usize)> = chunks.iter().enumerate()
+        .map(|(index, chunk)| {
+            let chunk = normalize(chunk);
+            (index, keywords.iter().filter(|keyword| chunk.contains(keyword.as_str())).count())
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let mut selected = Vec::new();
+    let mut tokens = 0;
+    for (index, _) in scored {
+        let chunk_tokens = chunking::estimate_tokens(&chunks[index]);
+        if tokens + chunk_tokens > budget {
+            continue;
+        }
+        tokens += chunk_tokens;
+        selected.push(index);
+    }
+    selected.sort();
+    return selected.iter().map(|index| chunks[*index].clone()).collect::<Vec<String>>().join("\n[...]\n");
+}
+
+/// Beginning of the text if it doesn't fit in the budget
+fn truncate(text: &str, budget: usize) -> String {
+    if chunking::estimate_tokens(text) <= budget {
+        return text.to_string();
+    }
+    match chunking::split_into_chunks(text, budget.saturating_sub(8)).into_iter().next() {
+        Some(beginning) if budget > 8 => return format!("{} [...]", beginning),
+        _ => return String::new(),
+    }
+}
+
+/// Quotes of the answer which are really in the transcript
+fn extract_quotes(answer: &str, transcript: &str) -> Vec<String> {
+    let transcript = normalize(transcript);
+    return answer.lines()
+        .filter_map(|line| line.trim().strip_prefix('>'))
+        .map(|quote| quote.trim().trim_matches(|c| c == '"' || c == '“' || c == '”').trim().to_string())
+        .filter(|quote| !quote.is_empty() && transcript.contains(&normalize(quote)))
+        .collect();
+}
+
+pub fn ask(meeting: &Meeting, history: &Vec<ConversationMessage>, question: &str) -> Result<ConversationMessage, Error> {
+    if meeting.transcript.trim().is_empty() && meeting.note.trim().is_empty() && meeting.summary.trim().is_empty() {
+        return Err(anyhow::anyhow!("Nothing to answer from: the meeting has no transcript, note or summary"));
+    }
+    let (llm, budget) = super::configured_llm(UsageContext::new(Some(meeting.get_uuid()), UsageOperation::Chat))?;
+
+    // Everything has to fit in the budget: the note & summary get up to half of it, the history what they leave
+    // -> The oldest messages are dropped first, the transcript takes the rest
+    let fixed_tokens = chunking::estimate_tokens(INSTRUCTION) + chunking::estimate_tokens(question) + 256;
+    if fixed_tokens >= budget {
+        return Err(anyhow::anyhow!("The question is too long for the model {}", llm.model()));
+    }
+    let available = budget - fixed_tokens;
+    let note = truncate(&meeting.note, available / 4);
+    let summary = truncate(&meeting.summary, available / 4);
+    let mut remaining = available.saturating_sub(chunking::estimate_tokens(&note) + chunking::estimate_tokens(&summary));
+    let mut kept = Vec::new();
+    for message in history.iter().rev().take(MAX_HISTORY_MESSAGES) {
+        let tokens = chunking::estimate_tokens(&message.content);
+        if tokens > remaining / 2 {
+            break;
+        }
+        remaining -= tokens;
+        kept.push(message);
+    }
+    kept.reverse();
+    let transcript = select_transcript(&meeting.transcript, question, remaining);
+    let context = format!(
+        "{} <title>{}</title> <date>{}</date> <note>{}</note> <summary>{}</summary> <transcript>{}</transcript>",
+        INSTRUCTION, meeting.title, meeting.datetime, note, summary, transcript
+    );
+
+    let mut messages = vec![LlmMessage::system(context)];
+    for message in kept {
+        let role = match message.role {
+            ConversationRole::User => LlmRole::User,
+            ConversationRole::Assistant => LlmRole::Assistant,
+        };
+        messages.push(LlmMessage { role, content: message.content.clone() });
+    }
+    messages.push(LlmMessage::user(question.to_string()));
+
+    let response = llm.complete(&LlmRequest::new(messages))?;
+    info!("[{}] answer received - model: {} - usage: {:?}", llm.name(), response.model, response.usage);
+    let quotes = extract_quotes(&response.content, &meeting.transcript);
+    return Ok(ConversationMessage::new(ConversationRole::Assistant, response.content, quotes));
+}
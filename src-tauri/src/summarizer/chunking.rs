@@ -0,0 +1,90 @@
+/*
+    * Token budget & chunking of long transcripts
+    * Tokens are estimated (~4 characters per token for English, a bit less for other languages),
+    * the estimation is conservative so a request never overflow the context window.
+*/
+
+// Room kept for the answer of the model
+const RESERVED_OUTPUT_TOKENS: usize = 4096;
+// Share of a chunk repeated at the start of the next one, so no sentence is lost at the boundary
+const OVERLAP_RATIO: usize = 10;
+
+pub fn estimate_tokens(text: &str) -> usize {
+    let chars = text.chars().count();
+    let words = text.split_whitespace().count();
+    return std::cmp::max((chars + 2) / 3, words * 4 / 3);
+}
+
+/// Number of tokens available for the prompt
+pub fn input_budget(context_window: usize) -> usize {
+    return context_window.saturating_sub(RESERVED_OUTPUT_TOKENS).max(1024);
+}
+
+/// Split on words, with an overlap between consecutive chunks
+pub fn split_into_chunks(text: &str, max_tokens: usize) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < words.len() {
+        let mut end = start;
+        let mut tokens = 0;
+        while end < words.len() {
+            let word_tokens = estimate_tokens(words[end]) + 1;
+            if tokens + word_tokens > max_tokens && end > start {
+                break;
+            }
+            tokens += word_tokens;
+            end += 1;
+        }
+        chunks.push(words[start..end].join(" "));
+        if end >= words.len() {
+            break;
+        }
+        let overlap = (end - start) / OVERLAP_RATIO;
+        start = std::cmp::max(end - overlap, start + 1);
+    }
+    return chunks;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("hello world"), 4);
+        // Many short words count more than their characters
+        assert_eq!(estimate_tokens("a a a a a a"), 8);
+    }
+
+    #[test]
+    fn keeps_room_for_the_answer() {
+        assert_eq!(input_budget(8192), 8192 - RESERVED_OUTPUT_TOKENS);
+        assert_eq!(input_budget(2000), 1024);
+    }
+
+    #[test]
+    fn splits_with_overlap_and_loses_no_word() {
+        let words: Vec<String> = (0..100).map(|index| format!("w{}", index)).collect();
+        let chunks = split_into_chunks(&words.join(" "), 20);
+        assert!(chunks.len() > 1);
+        assert!(chunks[0].starts_with("w0 "));
+        assert!(chunks.last().unwrap().ends_with("w99"));
+        for chunk in chunks.iter() {
+            let tokens: usize = chunk.split(' ').map(|word| estimate_tokens(word) + 1).sum();
+            assert!(tokens <= 20);
+        }
+        for word in words.iter() {
+            assert!(chunks.iter().any(|chunk| chunk.split(' ').any(|chunk_word| chunk_word == word)));
+        }
+        // The last word of a chunk is repeated at the start of the next one
+        let last_word = chunks[0].split(' ').last().unwrap();
+        assert!(chunks[1].split(' ').any(|word| word == last_word));
+    }
+
+    #[test]
+    fn splits_nothing() {
+        assert!(split_into_chunks("", 20).is_empty());
+    }
+}
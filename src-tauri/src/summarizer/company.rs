@@ -0,0 +1,47 @@
+/*
+    * Company digest
+    * Every meeting with a company, oldest first, summarized into what changed over time
+*/
+use anyhow::Error;
+use crate::model::{CompanyDigest, Meeting, UsageContext, UsageOperation};
+use crate::utils::markdown;
+use super::{summarize_transcript, SummaryObserver};
+
+const DIGEST_PROMPT: &str = "The transcript below is not a single meeting: it contains the summaries and notes of every meeting we had with {}, ordered by date. \
+    Write a cumulative digest of the relationship: \
+    - Timeline: what changed from one meeting to the next (team, product, traction, fundraising) \
+    - Metrics over time: every figure mentioned, with the date it was given \
+    - Open questions: what is still unanswered or needs to be checked \
+    Quote the date of the meeting for each fact.";
+
+/// Summary of the meeting if there is one - Else its note, else its transcript
+fn meeting_content(meeting: &Meeting) -> String {
+    if !meeting.summary.trim().is_empty() {
+        return meeting.summary.clone();
+    }
+    if !meeting.note.trim().is_empty() {
+        return markdown::from_html(&meeting.note);
+    }
+    return meeting.transcript.clone();
+}
+
+/// `meetings` must be ordered by date
+pub fn digest_company(company_id: &String, meetings: &Vec<Meeting>, force: bool) -> Result<CompanyDigest, Error> {
+    let company_name = meetings.iter()
+        .rev()
+        .map(|meeting| meeting.company_name.clone())
+        .find(|name| !name.trim().is_empty())
+        .unwrap_or(company_id.clone());
+    let content = meetings.iter()
+        .map(|meeting| format!("<meeting date=\"{}\" title=\"{}\">\n{}\n</meeting>", meeting.datetime, meeting.title, meeting_content(meeting)))
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    if content.trim().is_empty() {
+        return Err(anyhow::anyhow!("No meeting content to digest for {}", company_name));
+    }
+    // Long histories are condensed part by part like a long transcript
+    let prompt = DIGEST_PROMPT.replace("{}", &company_name);
+    let meeting_ids = meetings.iter().map(|meeting| meeting.get_uuid()).collect();
+    let response = summarize_transcript(content, Some(prompt), None, &SummaryObserver::default(), UsageContext::new(None, UsageOperation::CompanyDigest), meeting_ids, force)?;
+    return Ok(CompanyDigest::new(company_id.clone(), company_name, markdown::ensure_markdown(&response.content), meetings, response.model));
+}
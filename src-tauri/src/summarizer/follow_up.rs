@@ -0,0 +1,75 @@
+/*
+    * Follow-up email draft
+    * Written from the summary & action items, the transcript fills the remaining context
+*/
+use anyhow::Error;
+use chrono::Utc;
+use log::info;
+use serde::Deserialize;
+use crate::model::{FollowUpDraft, Meeting, UsageContext, UsageOperation};
+use super::chunking;
+use super::provider::{LlmMessage, LlmRequest};
+use super::template::render_template;
+
+pub const DEFAULT_FOLLOW_UP_PROMPT: &str = "Write a short and friendly follow-up email to {{company_name}} after our meeting of {{date}}. \
+    Thank them for their time, recap the key points discussed and list the next steps with their owner. \
+    Write it in {{language}}.";
+
+#[derive(Deserialize)]
+struct DraftResponse {
+    subject: String,
+    body: String,
+}
+
+fn build_prompt(instruction: &str, meeting: &Meeting, transcript: &str) -> String {
+    let action_items = meeting.action_items.clone().unwrap_or_default().iter()
+        .filter(|item| !item.done)
+        .map(|item| match &item.owner {
+            Some(owner) => format!("- {} ({})", item.task, owner),
+            None => format!("- {}", item.task),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    return format!(
+        "<Instruction>{}</Instruction> \
+        Answer ONLY with JSON, without markdown, in this format: {{\"subject\": \"...\", \"body\": \"plain text email\"}} \
+        <summary>{}</summary> <action_items>{}</action_items> <transcript>{}</transcript>",
+        instruction, meeting.summary, action_items, transcript
+    );
+}
+
+/// Same tolerance as the action items - The JSON can be wrapped in a code block or a sentence
+fn parse_response(content: &str) -> Result<DraftResponse, Error> {
+    let start = content.find('{');
+    let end = content.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => return Err(anyhow::anyhow!("The model did not answer with JSON: {}", content)),
+    };
+    return Ok(serde_json::from_str(json)?);
+}
+
+/// `template` is the email prompt of the settings - `{{variables}}` are replaced like in summary prompts
+pub fn draft_follow_up(meeting: &Meeting, template: &Option<String>) -> Result<FollowUpDraft, Error> {
+    if meeting.transcript.trim().is_empty() && meeting.summary.trim().is_empty() {
+        return Err(anyhow::anyhow!("No transcript or summary to write the follow-up from"));
+    }
+    let template = template.clone().filter(|template| !template.trim().is_empty()).unwrap_or(DEFAULT_FOLLOW_UP_PROMPT.to_string());
+    let instruction = render_template(&template, meeting)?;
+    let (llm, budget) = super::configured_llm(UsageContext::new(Some(meeting.get_uuid()), UsageOperation::FollowUp))?;
+    // The summary already covers the meeting - Only the beginning of a long transcript is sent
+    let transcript_budget = budget.saturating_sub(chunking::estimate_tokens(&build_prompt(&instruction, meeting, "")) + 256);
+    let transcript = match chunking::estimate_tokens(&meeting.transcript) > transcript_budget {
+        true => chunking::split_into_chunks(&meeting.transcript, transcript_budget).into_iter().next().unwrap_or_default(),
+        false => meeting.transcript.clone(),
+    };
+    let response = llm.complete(&LlmRequest::new(vec![LlmMessage::user(build_prompt(&instruction, meeting, &transcript))]))?;
+    info!("[{}] follow-up received - model: {} - usage: {:?}", llm.name(), response.model, response.usage);
+    let draft = parse_response(&response.content)?;
+    return Ok(FollowUpDraft {
+        subject: draft.subject.trim().to_string(),
+        body: draft.body.trim().to_string(),
+        model: response.model,
+        created_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    });
+}
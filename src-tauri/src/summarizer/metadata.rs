@@ -0,0 +1,133 @@
+/*
+    * Meeting metadata generation
+    * Run after the transcription - Title, company, participants & prompt are guessed from the transcript
+*/
+use anyhow::Error;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+use crate::model::{Meeting, Prompt, UsageContext, UsageOperation};
+use crate::{MeetingControllerState, SettingControllerState};
+use super::chunking;
+use super::provider::{LlmMessage, LlmRequest};
+
+#[derive(Clone, Debug, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct MeetingMetadata {
+    pub meeting_id: String,
+    pub title: Option<String>,
+    pub company_name: Option<String>,
+    pub company_id: Option<String>, // Affinity organization with the same name
+    pub participants: Vec<String>,
+    pub meeting_type: Option<String>, // One of the types of the prompt rules
+    pub suggested_prompt: Option<String>, // Name of a prompt of the settings
+}
+
+#[derive(Deserialize)]
+struct MetadataResponse {
+    title: Option<String>,
+    company_name: Option<String>,
+    #[serde(default)]
+    participants: Vec<String>,
+    meeting_type: Option<String>,
+    prompt: Option<String>,
+}
+
+fn quoted_list(names: Vec<&String>) -> String {
+    return names.iter()
+        .map(|name| format!("\"{}\"", name))
+        .collect::<Vec<String>>()
+        .join(", ");
+}
+
+fn build_prompt(transcript: &str, prompts: &Vec<Prompt>, meeting_types: &Vec<String>) -> String {
+    return format!(
+        "From the beginning of the meeting transcript, find: a concise title of the meeting (less than 8 words), \
+        the company we are meeting with (not our fund), the names of the participants, \
+        the type of the meeting among: [{}], \
+        and the prompt best suited to summarize it among: [{}]. \
+        Answer ONLY with JSON, without markdown, in this format: \
+        {{\"title\": \"...\", \"company_name\": \"name or null\", \"participants\": [\"...\"], \"meeting_type\": \"type or null\", \"prompt\": \"prompt name or null\"}} \
+        <transcript>{}</transcript>",
+        quoted_list(meeting_types.iter().collect()), quoted_list(prompts.iter().map(|prompt| &prompt.name).collect()), transcript
+    );
+}
+
+/// Same tolerance as the action items - The JSON can be wrapped in a code block or a sentence
+fn parse_response(content: &str) -> Result<MetadataResponse, Error> {
+    let start = content.find('{');
+    let end = content.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => return Err(anyhow::anyhow!("The model did not answer with JSON: {}", content)),
+    };
+    return Ok(serde_json::from_str(json)?);
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    return value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty() && value != "null");
+}
+
+pub fn generate_metadata(meeting: &Meeting, prompts: &Vec<Prompt>, meeting_types: &Vec<String>) -> Result<MeetingMetadata, Error> {
+    if meeting.transcript.trim().is_empty() {
+        return Err(anyhow::anyhow!("No transcript to generate the metadata from"));
+    }
+    let (llm, budget) = super::configured_llm(UsageContext::new(Some(meeting.get_uuid()), UsageOperation::Metadata))?;
+    // Introductions are at the beginning - The first part of a long transcript is enough
+    let chunk_budget = budget.saturating_sub(chunking::estimate_tokens(&build_prompt("", prompts, meeting_types)) + 64);
+    let transcript = match chunking::estimate_tokens(&meeting.transcript) > chunk_budget {
+        true => chunking::split_into_chunks(&meeting.transcript, chunk_budget).into_iter().next().unwrap_or_default(),
+        false => meeting.transcript.clone(),
+    };
+    let request = LlmRequest::new(vec![LlmMessage::user(build_prompt(&transcript, prompts, meeting_types))]);
+    let response = llm.complete(&request)?;
+    info!("[{}] metadata received - model: {} - usage: {:?}", llm.name(), response.model, response.usage);
+    let metadata = parse_response(&response.content)?;
+    let mut participants: Vec<String> = Vec::new();
+    for participant in metadata.participants.into_iter().filter_map(|participant| non_empty(Some(participant))) {
+        if !participants.iter().any(|known| known.to_lowercase() == participant.to_lowercase()) {
+            participants.push(participant);
+        }
+    }
+    let company_name = non_empty(metadata.company_name);
+    let company_id = match &company_name {
+        Some(name) => crate::crm::affinity::find_organization_id(name).unwrap_or_else(|error| {
+            warn!("[Metadata] No Affinity organization for {}: {:?}", name, error);
+            None
+        }),
+        None => None,
+    };
+    return Ok(MeetingMetadata {
+        meeting_id: meeting.get_uuid(),
+        title: non_empty(metadata.title),
+        company_name,
+        company_id,
+        participants,
+        // Only the types of the rules & the prompts of the settings can be suggested
+        meeting_type: non_empty(metadata.meeting_type)
+            .and_then(|detected| meeting_types.iter().find(|meeting_type| meeting_type.to_lowercase() == detected.to_lowercase()))
+            .cloned(),
+        suggested_prompt: non_empty(metadata.prompt)
+            .and_then(|name| prompts.iter().find(|prompt| prompt.name.to_lowercase() == name.to_lowercase()))
+            .map(|prompt| prompt.name.clone()),
+    });
+}
+
+/// Generate the metadata of a meeting & apply it - The controller is only locked to read & update the meeting
+pub fn generate_and_apply(app: &AppHandle, meeting_id: String) -> Result<MeetingMetadata, Error> {
+    let (meeting, setting) = {
+        let setting_controller = app.state::<SettingControllerState>();
+        let setting_controller = setting_controller.0.lock().unwrap();
+        let meeting_controller = app.state::<MeetingControllerState>();
+        let meeting_controller = meeting_controller.0.lock().unwrap();
+        (meeting_controller.get(meeting_id.clone())?, setting_controller.get_setting()?)
+    };
+    let prompts = setting.prompts.clone().unwrap_or_default();
+    let metadata = generate_metadata(&meeting, &prompts, &setting.meeting_types())?;
+    let meeting_controller = app.state::<MeetingControllerState>();
+    let mut meeting_controller = meeting_controller.0.lock().unwrap();
+    meeting_controller.apply_metadata(meeting_id, &metadata, &setting)?;
+    let _ = app.emit_all("MEETING_METADATA", metadata.clone());
+    return Ok(metadata);
+}
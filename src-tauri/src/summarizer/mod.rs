@@ -1 +1,206 @@
-pub mod openai;
\ No newline at end of file
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use anyhow::Error;
+use log::{error, info, warn};
+use serde::Serialize;
+use ts_rs::TS;
+use crate::model::{Setting, SettingController, UsageContext, UsageKind, UsageLedger};
+use crate::transcriber::CancelFlag;
+use provider::{LlmMessage, LlmProvider, LlmRequest, LlmResponse};
+
+pub mod action_items;
+pub mod anthropic;
+pub mod cache;
+pub mod chat;
+pub mod chunking;
+pub mod company;
+pub mod follow_up;
+pub mod metadata;
+pub mod openai;
+pub mod provider;
+pub mod template;
+
+#[derive(Clone, Debug, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum SummaryStage {
+    Chunk,   // Summarizing one part of a long transcript
+    Combine, // Final answer to the user prompt
+}
+
+#[derive(Clone, Debug, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct SummaryProgress {
+    pub stream_id: String,
+    pub meeting_id: String,
+    pub stage: SummaryStage,
+    pub step: usize,
+    pub total_steps: usize,
+}
+
+/// Piece of the answer, emitted as soon as the model generate it
+#[derive(Clone, Debug, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct SummaryToken {
+    pub stream_id: String,
+    pub meeting_id: String,
+    pub token: String,
+}
+
+/// Follow a summarization while it run
+#[derive(Clone)]
+pub struct SummaryObserver {
+    // Called before each request sent to the model -> (stage, step, total_steps)
+    pub progress: Arc<dyn Fn(SummaryStage, usize, usize) + Send + Sync>,
+    // Called with the tokens of the final answer only
+    pub token: Arc<dyn Fn(&str) + Send + Sync>,
+    pub cancel: CancelFlag,
+}
+
+impl Default for SummaryObserver {
+    fn default() -> Self {
+        Self {
+            progress: Arc::new(|_, _, _| {}),
+            token: Arc::new(|_| {}),
+            cancel: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SummaryCancelled;
+
+impl std::fmt::Display for SummaryCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Summary generation cancelled")
+    }
+}
+
+impl std::error::Error for SummaryCancelled {}
+
+fn build_prompt(transcript: &str, prompt: &Option<String>, condensed: bool) -> String {
+    let format = String::from("[VERY IMPORTANT: Answer in Markdown directly, without wrapping the answer in a code block. Don't use HTML tags, don't use # or ## headings, prefer ### headings and - bullet points instead]");
+    let notice = match condensed {
+        true => "The transcript was too long and has been condensed into chronological notes of each part of the meeting.\n",
+        false => "",
+    };
+    match prompt {
+        Some(prompt) => format!("{}<Instruction>{} \n&\n {} \n</Instruction> <transcript>{}</transcript>", notice, prompt, format, transcript),
+        None => format!("{}Refine and organize the provided transcript using bullet points. \n&\n {} \n<transcript>{}</transcript>", notice, format, transcript)
+    }
+}
+
+/// Map step - Summarize each part of the transcript, keeping what the final prompt could need
+fn condense(llm: &Box<dyn LlmProvider>, transcript: &str, budget: usize, observer: &SummaryObserver) -> Result<String, Error> {
+    let instruction = "You are given one part of a long meeting transcript. Write detailed notes of this part: discussion points, names, numbers, decisions, questions and action items. Don't add anything not in the transcript. Answer in plain text.";
+    let chunk_budget = budget.saturating_sub(chunking::estimate_tokens(instruction) + 64) / 2;
+    let chunks = chunking::split_into_chunks(transcript, chunk_budget);
+    info!("[Summarizer] Transcript too long - split into {} chunks", chunks.len());
+    let mut notes = Vec::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        (observer.progress)(SummaryStage::Chunk, index + 1, chunks.len());
+        let prompt = format!("{} <part number=\"{}/{}\">{}</part>", instruction, index + 1, chunks.len(), chunk);
+        let response = llm.stream(&LlmRequest::new(vec![LlmMessage::user(prompt)]), &|_| {}, &observer.cancel)?;
+        notes.push(format!("Part {}/{}:\n{}", index + 1, chunks.len(), response.content));
+    }
+    return Ok(notes.join("\n\n"));
+}
+
+/// Configured model & the number of tokens its prompt can hold
+/// -> Fail if the monthly budget is reached, the usage of each request is recorded
+pub fn configured_llm(context: UsageContext) -> Result<(Box<dyn LlmProvider>, usize), Error> {
+    let setting = SettingController::new(crate::model::SettingPath::Default).get_setting()?;
+    return setting_llm(&setting, context);
+}
+
+fn setting_llm(setting: &Setting, context: UsageContext) -> Result<(Box<dyn LlmProvider>, usize), Error> {
+    UsageLedger::check_budget(UsageKind::Llm)?;
+    let llm: Box<dyn LlmProvider> = Box::new(provider::MeteredProvider::new(provider::from_setting(setting)?, context));
+    let budget = chunking::input_budget(provider::context_window(&provider::llm_setting(setting)));
+    return Ok((llm, budget));
+}
+
+/// Settings with the model chosen for the meeting - The configured one if None
+fn model_setting(model: &Option<String>) -> Result<Setting, Error> {
+    let setting = SettingController::new(crate::model::SettingPath::Default).get_setting()?;
+    match model {
+        Some(model) => return Ok(provider::with_model(&setting, model)),
+        None => return Ok(setting),
+    }
+}
+
+/// Prompt sent by `summarize_transcript` when the transcript fit in the context window
+pub fn preview_prompt(transcript: &str, prompt: &Option<String>, model: &Option<String>) -> Result<template::PromptPreview, Error> {
+    let setting = model_setting(model)?;
+    let budget = chunking::input_budget(provider::context_window(&provider::llm_setting(&setting)));
+    let prompt = build_prompt(transcript, prompt, false);
+    let estimated_tokens = chunking::estimate_tokens(&prompt);
+    return Ok(template::PromptPreview { prompt, estimated_tokens, exceeds_context: estimated_tokens > budget });
+}
+
+/// `model` replaces the configured one - Set by a prompt rule
+/// `force` skips the response cache - The answer is generated again and replaces the cached one
+/// `meeting_ids` are the meetings the transcript comes from - Their cached answers are deleted with them
+pub fn summarize_transcript(transcript: String, prompt: Option<String>, model: Option<String>, observer: &SummaryObserver, context: UsageContext, meeting_ids: Vec<String>, force: bool) -> Result<LlmResponse, Error> {
+
+    // A cached answer is free -> Checked before the budget
+    let setting = model_setting(&model)?;
+    let cache_key = cache::key(
+        provider::from_setting(&setting)?.as_ref(),
+        &LlmRequest::new(Vec::new()), // Same parameters as the requests below
+        chunking::input_budget(provider::context_window(&provider::llm_setting(&setting))),
+        &build_prompt(&transcript, &prompt, false),
+    );
+    if !force {
+        if let Some(response) = cache::get(&cache_key) {
+            (observer.token)(&response.content);
+            return Ok(response);
+        }
+    }
+
+    let (llm, budget) = setting_llm(&setting, context)?;
+
+    let mut transcript = transcript;
+    let mut condensed = false;
+    // Notes of a very long meeting can still be too long -> Condense them again
+    for _ in 0..3 {
+        let tokens = chunking::estimate_tokens(&build_prompt(&transcript, &prompt, condensed));
+        if tokens <= budget {
+            break;
+        }
+        info!("[Summarizer] ~{} tokens for a budget of {} tokens", tokens, budget);
+        transcript = match condense(&llm, &transcript, budget, observer) {
+            Ok(notes) => notes,
+            Err(error) if error.is::<SummaryCancelled>() => return Err(error),
+            Err(error) => {
+                error!("Error while condensing the transcript: {:?}", error);
+                return Err(anyhow::anyhow!("Error occured in the summarization of a part of the transcript: {:?}", error));
+            }
+        };
+        condensed = true;
+    }
+    let final_prompt = build_prompt(&transcript, &prompt, condensed);
+    let tokens = chunking::estimate_tokens(&final_prompt);
+    if tokens > budget {
+        return Err(anyhow::anyhow!("The transcript is too long for the model {}: ~{} tokens after condensing it, for a budget of {} tokens", llm.model(), tokens, budget));
+    }
+
+    (observer.progress)(SummaryStage::Combine, 1, 1);
+    let request = LlmRequest::new(vec![LlmMessage::user(final_prompt)]);
+    match llm.stream(&request, &*observer.token, &observer.cancel) {
+        Ok(response) => {
+            info!("[{}] response received - model: {} - usage: {:?}", llm.name(), response.model, response.usage);
+            if let Err(error) = cache::put(&cache_key, &response, &meeting_ids) {
+                warn!("[Summarizer] Response not cached: {:?}", error);
+            }
+            return Ok(response);
+        },
+        Err(error) if error.is::<SummaryCancelled>() => {
+            info!("[Summarizer] Generation cancelled");
+            return Err(error);
+        },
+        Err(error) => {
+            error!("Error in summarization: {:?}", error);
+            return Err(anyhow::anyhow!("Error occured in the summarization: {:?}", error));
+        }
+    }
+}
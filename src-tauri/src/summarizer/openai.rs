@@ -1,10 +1,12 @@
 use anyhow::Error;
-use openai_api_rust::*;
-use openai_api_rust::chat::*;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-use crate::model::SettingController;
-use log::{error, info};
+use log::info;
+use crate::transcriber::CancelFlag;
+use super::provider::{read_events, request_error, LlmProvider, LlmRequest, LlmResponse, LlmUsage, TokenCallback};
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const AZURE_API_VERSION: &str = "2024-06-01";
 
 #[derive(Clone, Deserialize, Serialize, TS)]
 #[ts(export, export_to = "../src/bindings/")]
@@ -27,48 +29,190 @@ impl ModelTurbo {
     }
 }
 
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatUsage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    model: Option<String>,
+    choices: Vec<ChatChoice>,
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Deserialize)]
+struct ChatChunkChoice {
+    delta: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatChunk {
+    model: Option<String>,
+    #[serde(default)]
+    choices: Vec<ChatChunkChoice>,
+    usage: Option<ChatUsage>,
+}
+
+enum Auth {
+    Bearer(Option<String>),
+    AzureKey(String),
+}
+
+/// OpenAI chat completion API - Also spoken by Azure OpenAI, Ollama, vLLM, LM Studio, ...
+pub struct OpenAIProvider {
+    agent: ureq::Agent,
+    name: &'static str,
+    endpoint: String,
+    auth: Auth,
+    model: String,
+    // `stream_options` is not understood by every compatible server
+    stream_usage: bool,
+}
+
+impl OpenAIProvider {
+    pub fn openai(agent: ureq::Agent, base_url: Option<String>, api_key: String, model: String) -> Self {
+        let base_url = base_url.filter(|url| !url.is_empty()).unwrap_or(OPENAI_BASE_URL.to_string());
+        Self {
+            agent,
+            name: "OpenAI",
+            endpoint: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+            auth: Auth::Bearer(Some(api_key)),
+            model,
+            stream_usage: true,
+        }
+    }
+
+    pub fn azure(agent: ureq::Agent, base_url: String, api_key: String, deployment: String, api_version: Option<String>) -> Self {
+        let api_version = api_version.filter(|version| !version.is_empty()).unwrap_or(AZURE_API_VERSION.to_string());
+        Self {
+            agent,
+            name: "Azure OpenAI",
+            endpoint: format!("{}/openai/deployments/{}/chat/completions?api-version={}", base_url.trim_end_matches('/'), deployment, api_version),
+            auth: Auth::AzureKey(api_key),
+            model: deployment,
+            stream_usage: true,
+        }
+    }
+
+    pub fn compatible(agent: ureq::Agent, base_url: String, api_key: Option<String>, model: String) -> Self {
+        Self {
+            agent,
+            name: "OpenAI compatible",
+            endpoint: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+            auth: Auth::Bearer(api_key),
+            model,
+            stream_usage: false,
+        }
+    }
+
+    fn request(&self) -> ureq::Request {
+        let request = self.agent.post(&self.endpoint).set("Content-Type", "application/json");
+        match &self.auth {
+            Auth::Bearer(Some(api_key)) => request.set("Authorization", &format!("Bearer {}", api_key)),
+            Auth::Bearer(None) => request,
+            Auth::AzureKey(api_key) => request.set("api-key", api_key),
+        }
+    }
+
+    fn body(&self, request: &LlmRequest) -> serde_json::Value {
+        let messages: Vec<serde_json::Value> = request.messages.iter()
+            .map(|message| serde_json::json!({ "role": message.role.as_str(), "content": message.content }))
+            .collect();
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "messages": messages,
+        });
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = serde_json::json!(temperature);
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            body["max_tokens"] = serde_json::json!(max_tokens);
+        }
+        return body;
+    }
+}
+
+impl LlmProvider for OpenAIProvider {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn model(&self) -> String {
+        self.model.clone()
+    }
+
+    fn endpoint(&self) -> String {
+        self.endpoint.clone()
+    }
 
-pub fn summarize_with_openai(transcript: String, prompt: Option<String>) -> Result<String, Error> {
-
-    let setting = SettingController::new(crate::model::SettingPath::Default).get_setting().unwrap();
-    let model = match setting.default_model {
-        Some(model) => model,
-        None => ModelTurbo::GPT4oMini,
-    };
-    info!("OpenAI model: {}", model.as_str());
-    let format = String::from("[VERY IMPORTANT: Answer in HTML format directly, Without the header <!DOCTYPE html><html><head> ...</head>), don't include <body> tag don't use <h1> tag, prefer using <h4> and <li> tags instead");
-
-    let prompt = match prompt {
-        Some(prompt) => format!("<Instruction>{} \n&\n {} \n</Instruction> <transcript>{}/transcipt>", prompt, format, transcript),
-        None => format!("Refine and organize the provided transcript using bullet points. \n&\n {} \n<transcript>{}</transcript>", format, transcript)
-    };
-
-    let auth = Auth::new(&setting.openai_api_token);
-    let openai = OpenAI::new(auth, "https://api.openai.com/v1/");
-    let body: ChatBody = ChatBody {
-        model: model.as_str().to_string(),
-        max_tokens: None,
-        temperature: Some(0_f32),
-        top_p: Some(0_f32),
-        n: Some(2),
-        stream: Some(false),
-        stop: None,
-        presence_penalty: None,
-        frequency_penalty: None,
-        logit_bias: None,
-        user: None,
-        messages: vec![Message { role: Role::User, content: prompt }],
-    };
-    let rs = openai.chat_completion_create(&body);
-    info!("OpenAI response: {:?}", rs);
-    let choice = match rs {
-        Ok(rs) => rs.choices,
-        Err(e) => {
-            error!("Error in transcription: {:?}", e);
-            return Err(anyhow::anyhow!("Error occured in the summarization: {:?}", e));
+    fn complete(&self, request: &LlmRequest) -> Result<LlmResponse, Error> {
+        info!("[{}] model: {}", self.name, self.model);
+        let response: ChatResponse = match self.request().send_json(self.body(request)) {
+            Ok(response) => response.into_json()?,
+            Err(error) => return Err(request_error(self.name, error)),
+        };
+        let content = match response.choices.into_iter().next() {
+            Some(choice) => choice.message.content.unwrap_or_default(),
+            None => return Err(anyhow::anyhow!("[{}] The model returned no answer", self.name)),
+        };
+        return Ok(LlmResponse {
+            content,
+            model: response.model.unwrap_or(self.model.clone()),
+            usage: response.usage.map(|usage| LlmUsage {
+                prompt_tokens: usage.prompt_tokens,
+                completion_tokens: usage.completion_tokens,
+            }),
+        });
+    }
+
+    fn stream(&self, request: &LlmRequest, on_token: TokenCallback, cancel: &CancelFlag) -> Result<LlmResponse, Error> {
+        info!("[{}] model: {} - streaming", self.name, self.model);
+        let mut body = self.body(request);
+        body["stream"] = serde_json::json!(true);
+        if self.stream_usage {
+            body["stream_options"] = serde_json::json!({ "include_usage": true });
         }
-    };
-    let message = &choice[0].message.as_ref().unwrap();
+        let response = match self.request().send_json(body) {
+            Ok(response) => response,
+            Err(error) => return Err(request_error(self.name, error)),
+        };
 
-    return Ok(message.content.clone());
+        let mut content = String::new();
+        let mut model = self.model.clone();
+        let mut usage = None;
+        read_events(response, cancel, |data| {
+            if data == "[DONE]" {
+                return Ok(false);
+            }
+            let chunk: ChatChunk = serde_json::from_str(data)?;
+            if let Some(chunk_model) = chunk.model.filter(|chunk_model| !chunk_model.is_empty()) {
+                model = chunk_model;
+            }
+            if let Some(chunk_usage) = chunk.usage {
+                usage = Some(LlmUsage {
+                    prompt_tokens: chunk_usage.prompt_tokens,
+                    completion_tokens: chunk_usage.completion_tokens,
+                });
+            }
+            // Only one choice is requested
+            if let Some(token) = chunk.choices.into_iter().next().and_then(|choice| choice.delta.content) {
+                on_token(&token);
+                content.push_str(&token);
+            }
+            return Ok(true);
+        })?;
+        return Ok(LlmResponse { content, model, usage });
+    }
 }
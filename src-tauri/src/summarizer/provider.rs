@@ -0,0 +1,272 @@
+/*
+    * LLM provider abstraction
+    * Every provider receive the same chat request, so the summarizer doesn't care where the model run
+*/
+use std::io::{BufRead, BufReader};
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use anyhow::Error;
+use crate::model::{LlmProviderKind, LlmSetting, Setting, UsageContext, UsageEntry, UsageLedger};
+use crate::transcriber::CancelFlag;
+use super::SummaryCancelled;
+use super::chunking;
+use super::anthropic::AnthropicProvider;
+use super::openai::{ModelTurbo, OpenAIProvider};
+
+// Long transcripts can take a while to be processed
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LlmRole {
+    System,
+    User,
+    Assistant,
+}
+
+impl LlmRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LlmRole::System => "system",
+            LlmRole::User => "user",
+            LlmRole::Assistant => "assistant",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LlmMessage {
+    pub role: LlmRole,
+    pub content: String,
+}
+
+impl LlmMessage {
+    pub fn user(content: String) -> Self {
+        Self { role: LlmRole::User, content }
+    }
+
+    pub fn system(content: String) -> Self {
+        Self { role: LlmRole::System, content }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LlmRequest {
+    pub messages: Vec<LlmMessage>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+impl LlmRequest {
+    pub fn new(messages: Vec<LlmMessage>) -> Self {
+        Self {
+            messages,
+            temperature: Some(0_f32),
+            max_tokens: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LlmUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct LlmResponse {
+    pub content: String,
+    pub model: String,
+    pub usage: Option<LlmUsage>,
+}
+
+/// Called with each piece of text as soon as the model generate it
+pub type TokenCallback<'a> = &'a (dyn Fn(&str) + Send + Sync);
+
+pub trait LlmProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn model(&self) -> String;
+    /// URL the requests are sent to - Two servers can serve models with the same name
+    fn endpoint(&self) -> String;
+    fn complete(&self, request: &LlmRequest) -> Result<LlmResponse, Error>;
+    /// Same as `complete`, the answer is also given piece by piece to `on_token`
+    /// -> Stop reading the answer and return `SummaryCancelled` once `cancel` is set
+    fn stream(&self, request: &LlmRequest, on_token: TokenCallback, cancel: &CancelFlag) -> Result<LlmResponse, Error>;
+}
+
+/// Record the usage of each request in the ledger
+pub struct MeteredProvider {
+    inner: Box<dyn LlmProvider>,
+    context: UsageContext,
+}
+
+impl MeteredProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, context: UsageContext) -> Self {
+        Self { inner, context }
+    }
+
+    fn estimate(request: &LlmRequest, content: &str) -> LlmUsage {
+        return LlmUsage {
+            prompt_tokens: request.messages.iter().map(|message| chunking::estimate_tokens(&message.content) as u32).sum(),
+            completion_tokens: chunking::estimate_tokens(content) as u32,
+        };
+    }
+
+    fn record(&self, request: &LlmRequest, response: &LlmResponse) {
+        // Compatible servers don't always give the usage
+        let usage = response.usage.clone().unwrap_or(Self::estimate(request, &response.content));
+        UsageLedger::record(UsageEntry::llm(&self.context, self.inner.name(), &response.model, usage.prompt_tokens, usage.completion_tokens));
+    }
+}
+
+impl LlmProvider for MeteredProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> String {
+        self.inner.model()
+    }
+
+    fn endpoint(&self) -> String {
+        self.inner.endpoint()
+    }
+
+    fn complete(&self, request: &LlmRequest) -> Result<LlmResponse, Error> {
+        let response = self.inner.complete(request)?;
+        self.record(request, &response);
+        return Ok(response);
+    }
+
+    fn stream(&self, request: &LlmRequest, on_token: TokenCallback, cancel: &CancelFlag) -> Result<LlmResponse, Error> {
+        // Keep what was streamed, the tokens are billed even if the answer never completes
+        let streamed = Mutex::new(String::new());
+        let on_token_metered = |token: &str| {
+            if let Ok(mut streamed) = streamed.lock() {
+                streamed.push_str(token);
+            }
+            on_token(token);
+        };
+        match self.inner.stream(request, &on_token_metered, cancel) {
+            Ok(response) => {
+                self.record(request, &response);
+                return Ok(response);
+            },
+            Err(error) => {
+                let streamed = streamed.lock().map(|streamed| streamed.clone()).unwrap_or_default();
+                // The prompt was processed once the generation started or was cancelled
+                if !streamed.is_empty() || error.is::<SummaryCancelled>() {
+                    let usage = Self::estimate(request, &streamed);
+                    UsageLedger::record(UsageEntry::llm(&self.context, self.inner.name(), &self.inner.model(), usage.prompt_tokens, usage.completion_tokens));
+                }
+                return Err(error);
+            }
+        }
+    }
+}
+
+/// Settings saved before the provider was configurable only have an OpenAI token & model
+pub fn llm_setting(setting: &Setting) -> LlmSetting {
+    match &setting.llm {
+        Some(llm) => llm.clone(),
+        None => LlmSetting {
+            provider: LlmProviderKind::OpenAI,
+            model: setting.default_model.clone().unwrap_or(ModelTurbo::GPT4oMini).as_str().to_string(),
+            base_url: None,
+            api_key: None,
+            api_version: None,
+            context_window: None,
+        },
+    }
+}
+
+/// Same provider with another model - Set by a prompt rule
+pub fn with_model(setting: &Setting, model: &str) -> Setting {
+    let mut setting = setting.clone();
+    let llm = llm_setting(&setting);
+    setting.llm = Some(LlmSetting {
+        model: model.to_string(),
+        // The configured context window is the one of the configured model
+        context_window: llm.context_window.filter(|_| llm.model == model),
+        ..llm
+    });
+    return setting;
+}
+
+/// Context window of the model, in tokens - Local models are assumed to be small
+pub fn context_window(llm: &LlmSetting) -> usize {
+    if let Some(context_window) = llm.context_window {
+        return context_window as usize;
+    }
+    let model = llm.model.to_lowercase();
+    if model.starts_with("claude") {
+        return 200_000;
+    } else if model.starts_with("gpt-4o") || model.starts_with("gpt-4.1") || model.starts_with("gpt-4-turbo") || model.starts_with("o1") || model.starts_with("o3") || model.starts_with("o4") {
+        return 128_000;
+    } else if model.starts_with("gpt-3.5") {
+        return 16_385;
+    } else {
+        return 8_192;
+    }
+}
+
+pub fn from_setting(setting: &Setting) -> Result<Box<dyn LlmProvider>, Error> {
+    let llm = llm_setting(setting);
+    if llm.model.trim().is_empty() {
+        return Err(anyhow::anyhow!("No model configured for the summarization"));
+    }
+    let agent = ureq::AgentBuilder::new().timeout_read(REQUEST_TIMEOUT).build();
+    let api_key = llm.api_key.clone().filter(|api_key| !api_key.is_empty());
+    match llm.provider {
+        LlmProviderKind::OpenAI => {
+            let api_key = api_key.unwrap_or(setting.openai_api_token.clone());
+            Ok(Box::new(OpenAIProvider::openai(agent, llm.base_url, api_key, llm.model)))
+        },
+        LlmProviderKind::AzureOpenAI => {
+            let base_url = llm.base_url.ok_or(anyhow::anyhow!("Azure OpenAI needs the resource endpoint as base URL"))?;
+            let api_key = api_key.ok_or(anyhow::anyhow!("Azure OpenAI needs an API key"))?;
+            Ok(Box::new(OpenAIProvider::azure(agent, base_url, api_key, llm.model, llm.api_version)))
+        },
+        LlmProviderKind::OpenAICompatible => {
+            let base_url = llm.base_url.ok_or(anyhow::anyhow!("An OpenAI compatible server needs a base URL"))?;
+            Ok(Box::new(OpenAIProvider::compatible(agent, base_url, api_key, llm.model)))
+        },
+        LlmProviderKind::Anthropic => {
+            let api_key = api_key.ok_or(anyhow::anyhow!("Anthropic needs an API key"))?;
+            Ok(Box::new(AnthropicProvider::new(agent, llm.base_url, api_key, llm.model)))
+        },
+    }
+}
+
+/// Read a server-sent events body, `on_data` receive the payload of each `data:` line
+/// -> Return false from `on_data` to stop reading
+pub fn read_events(response: ureq::Response, cancel: &CancelFlag, mut on_data: impl FnMut(&str) -> Result<bool, Error>) -> Result<(), Error> {
+    let reader = BufReader::new(response.into_reader());
+    for line in reader.lines() {
+        if cancel.load(Ordering::SeqCst) {
+            // Dropping the reader close the connection, the provider stop the generation
+            return Err(anyhow::anyhow!(SummaryCancelled));
+        }
+        let line = line?;
+        if let Some(data) = line.strip_prefix("data:") {
+            if !on_data(data.trim())? {
+                break;
+            }
+        }
+    }
+    if cancel.load(Ordering::SeqCst) {
+        return Err(anyhow::anyhow!(SummaryCancelled));
+    }
+    return Ok(());
+}
+
+/// Turn an HTTP error into a readable message - The body usually explain what is wrong
+pub fn request_error(provider: &str, error: ureq::Error) -> Error {
+    match error {
+        ureq::Error::Status(code, response) => {
+            let body = response.into_string().unwrap_or_default();
+            anyhow::anyhow!("[{}] Request failed with status code {}: {}", provider, code, body)
+        },
+        ureq::Error::Transport(transport) => anyhow::anyhow!("[{}] Request failed: {}", provider, transport),
+    }
+}
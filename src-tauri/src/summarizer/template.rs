@@ -0,0 +1,152 @@
+/*
+    * Prompt templates
+    * `{{variable}}` in a prompt is replaced by the value of the meeting before it is sent
+    * `{{{{` writes a literal `{{`
+*/
+use anyhow::Error;
+use serde::Serialize;
+use ts_rs::TS;
+use crate::model::Meeting;
+use crate::transcriber::language;
+
+pub const TEMPLATE_VARIABLES: &[&str] = &["company_name", "title", "date", "attendees", "note", "language"];
+
+#[derive(Clone, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct PromptPreview {
+    pub prompt: String,
+    pub estimated_tokens: usize,
+    // The transcript will be condensed part by part before this prompt is sent
+    pub exceeds_context: bool,
+}
+
+enum Piece<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+/// `{{{{` is a literal `{{` - Braces around something that is not a variable name are kept as they are (e.g. JSON)
+fn parse(template: &str) -> Result<Vec<Piece>, Error> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if rest[start..].starts_with("{{{{") {
+            pieces.push(Piece::Text(&rest[..start + 2]));
+            rest = &rest[start + 4..];
+            continue;
+        }
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        let name = rest[start + 2..end].trim();
+        if !is_variable_name(name) {
+            pieces.push(Piece::Text(&rest[..end + 2]));
+            rest = &rest[end + 2..];
+            continue;
+        }
+        if !TEMPLATE_VARIABLES.contains(&name) {
+            return Err(anyhow::anyhow!("Unknown variable `{{{{{}}}}}` in prompt - Available variables: {} - Write `{{{{{{{{` for a literal `{{{{`", name, TEMPLATE_VARIABLES.join(", ")));
+        }
+        pieces.push(Piece::Text(&rest[..start]));
+        pieces.push(Piece::Variable(name));
+        rest = &rest[end + 2..];
+    }
+    pieces.push(Piece::Text(rest));
+    return Ok(pieces);
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    return match chars.next() {
+        Some(first) => (first.is_ascii_alphabetic() || first == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        None => false,
+    };
+}
+
+pub fn validate_template(template: &str) -> Result<(), Error> {
+    parse(template)?;
+    return Ok(());
+}
+
+/// Participants found in the meeting, or the speakers of the transcript
+/// -> The diarization only give labels (A, B, ...)
+fn attendees(meeting: &Meeting) -> String {
+    if let Some(participants) = meeting.participants.clone().filter(|participants| !participants.is_empty()) {
+        return participants.join(", ");
+    }
+    let mut speakers: Vec<String> = Vec::new();
+    for segment in meeting.segments.clone().unwrap_or_default() {
+        if let Some(speaker) = segment.speaker {
+            if !speakers.contains(&speaker) {
+                speakers.push(speaker);
+            }
+        }
+    }
+    return speakers.join(", ");
+}
+
+fn value(name: &str, meeting: &Meeting) -> String {
+    match name {
+        "company_name" => meeting.company_name.clone(),
+        "title" => meeting.title.clone(),
+        "date" => match chrono::DateTime::parse_from_rfc3339(&meeting.datetime) {
+            Ok(datetime) => datetime.format("%Y-%m-%d").to_string(),
+            Err(_) => meeting.datetime.clone(),
+        },
+        "attendees" => attendees(meeting),
+        "note" => meeting.note.clone(),
+        "language" => match &meeting.detected_language {
+            Some(code) => language::language_name(code).unwrap_or(code.clone()),
+            None => String::new(),
+        },
+        _ => String::new(),
+    }
+}
+
+pub fn render_template(template: &str, meeting: &Meeting) -> Result<String, Error> {
+    let mut rendered = String::new();
+    for piece in parse(template)? {
+        match piece {
+            Piece::Text(text) => rendered.push_str(text),
+            Piece::Variable(name) => rendered.push_str(&value(name, meeting)),
+        }
+    }
+    return Ok(rendered);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Variables are written `<name>` so the tests don't need a meeting
+    fn parsed(template: &str) -> String {
+        return parse(template).unwrap().iter().map(|piece| match piece {
+            Piece::Text(text) => text.to_string(),
+            Piece::Variable(name) => format!("<{}>", name),
+        }).collect();
+    }
+
+    #[test]
+    fn finds_variables() {
+        assert_eq!(parsed("Summarize {{title}} for {{ company_name }}"), "Summarize <title> for <company_name>");
+    }
+
+    #[test]
+    fn escapes_braces() {
+        assert_eq!(parsed("Literal {{{{title}} here"), "Literal {{title}} here");
+    }
+
+    #[test]
+    fn keeps_braces_that_are_not_variables() {
+        let json = r#"Answer as {{"name": "value"}} only"#;
+        assert_eq!(parsed(json), json);
+        assert_eq!(parsed("Unclosed {{ brace"), "Unclosed {{ brace");
+        assert!(validate_template(json).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_variables() {
+        assert!(validate_template("Hello {{titel}}").is_err());
+    }
+}
@@ -0,0 +1,341 @@
+/*
+    * AssemblyAI client
+    * Stream the audio from disk, retry on transient errors and can be cancelled at any time
+*/
+use std::cmp::min;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use anyhow::Error;
+use futures_util::{stream, StreamExt};
+use log::{error, info, warn};
+use reqwest::{Body, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use crate::model::{Chapter, RedactionMode, SettingController, TranscriptSegment};
+use super::language::{resolve_language, LanguageOption};
+use super::redaction;
+use super::{CancelFlag, ProgressCallback, TranscriptionCancelled, TranscriptionOptions, TranscriptionStage};
+
+const BASE_URL: &str = "https://api.assemblyai.com/v2";
+const UPLOAD_CHUNK_SIZE: usize = 1024 * 1024;
+const POLLING_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Deserialize)]
+pub struct TranscriptResponse {
+    pub id: String,
+    pub language_model: Option<String>,
+    pub acoustic_model: Option<String>,
+    pub language_code: Option<String>,
+    pub language_confidence: Option<f32>,
+    pub audio_url: String,
+    pub status: String,
+    pub text: Option<String>,
+    pub error: Option<String>,
+    pub chapters: Option<Vec<Chapter>>,
+    pub words: Option<Vec<Word>>,
+    pub audio_duration: Option<f64>, // Seconds
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Word {
+    pub text: String,
+    pub start: i32, // ms
+    pub end: i32,   // ms
+}
+
+#[derive(Deserialize)]
+struct RedactedAudioResponse {
+    status: String,
+    redacted_audio_url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct StartingTranscriptResponse {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct SentencesResponse {
+    sentences: Vec<TranscriptSegment>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct UploadResponse {
+    upload_url: String,
+}
+
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+pub struct AssemblyAIClient {
+    client: reqwest::Client,
+    api_token: String,
+    retry_policy: RetryPolicy,
+}
+
+impl AssemblyAIClient {
+    pub fn new(api_token: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_token,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    pub fn from_setting() -> Self {
+        let setting = SettingController::new(crate::model::SettingPath::Default).get_setting().unwrap();
+        return Self::new(setting.assemblyai_api_token);
+    }
+
+    /// Upload the audio and start the transcription
+    /// -> Return the AssemblyAI transcript id, which can be used to resume the polling later on
+    pub async fn submit(&self, audio_path: &String, options: &TranscriptionOptions, progress: ProgressCallback, cancel: &CancelFlag) -> Result<String, Error> {
+        let upload_url = self.upload(audio_path, progress, cancel).await?;
+        return self.start(&upload_url, options, cancel).await;
+    }
+
+    pub async fn upload(&self, audio_path: &String, progress: ProgressCallback, cancel: &CancelFlag) -> Result<String, Error> {
+        info!("Uploading audio to assemblyAI...");
+        let response = self.send_with_retry(cancel, || {
+            let body = file_body(audio_path, progress.clone(), cancel.clone())?;
+            Ok(self.client
+                .post(&format!("{}/upload", BASE_URL))
+                .header("authorization", self.api_token.clone())
+                .body(body))
+        }).await?;
+
+        if response.status() != StatusCode::OK {
+            error!("Uploading audio to AssemblyAI failed with status code: {:?} - {:?}", response.status(),  response.text().await?);
+            return Err(anyhow::Error::msg("Transcription failed while uploading audio"));
+        }
+        let upload_url = response.json::<UploadResponse>().await?.upload_url;
+        return Ok(upload_url);
+    }
+
+    pub async fn start(&self, upload_url: &String, options: &TranscriptionOptions, cancel: &CancelFlag) -> Result<String, Error> {
+        let mut data = serde_json::json!({
+            "audio_url": upload_url,
+            "auto_chapters": false, // Interesting feature, but costly for now
+            "speaker_labels": true,
+        });
+        match resolve_language(options.language.clone()) {
+            LanguageOption::AutoDetect => data["language_detection"] = serde_json::json!(true),
+            LanguageOption::Code(language_code) => data["language_code"] = serde_json::json!(language_code),
+        }
+        if !options.word_boost.is_empty() {
+            data["word_boost"] = serde_json::json!(options.word_boost);
+            data["boost_param"] = serde_json::json!("high");
+        }
+        if let Some(policy) = options.redaction.as_ref().filter(|policy| policy.enabled && policy.mode == RedactionMode::Provider) {
+            data["redact_pii"] = serde_json::json!(true);
+            data["redact_pii_policies"] = serde_json::json!(redaction::provider_policies(policy));
+            data["redact_pii_sub"] = serde_json::json!("entity_name");
+            data["redact_pii_audio"] = serde_json::json!(policy.redact_audio);
+        }
+
+        info!("Start transcription...");
+        let response = self.send_with_retry(cancel, || {
+            Ok(self.client
+                .post(&format!("{}/transcript", BASE_URL))
+                .header("authorization", self.api_token.clone())
+                .json(&data))
+        }).await?;
+
+        if response.status() != StatusCode::OK {
+            error!("Starting request for transcription with assemblyAI failed with status code: {:?} - {:?}", response.status(),  response.text().await?);
+            return Err(anyhow::Error::msg("Transcription failed while starting transcription"));
+        }
+        let transcript_id = response.json::<StartingTranscriptResponse>().await?.id;
+        return Ok(transcript_id);
+    }
+
+    /// Wait for AssemblyAI to complete the transcription
+    pub async fn poll(&self, transcript_id: &String, progress: ProgressCallback, cancel: &CancelFlag) -> Result<TranscriptResponse, Error> {
+        let polling_endpoint = format!("{}/transcript/{}", BASE_URL, transcript_id);
+        info!("Start polling...");
+
+        loop {
+            let response = self.send_with_retry(cancel, || {
+                Ok(self.client
+                    .get(&polling_endpoint)
+                    .header("authorization", self.api_token.clone()))
+            }).await?;
+
+            if response.status() != StatusCode::OK {
+                error!("Request failed with status code: {:?} - {:?}", response.status(),  response.text().await?);
+                return Err(anyhow::Error::msg("Transcription failed while polling transcription"));
+            }
+
+            let transcription_result: TranscriptResponse = response.json().await?;
+            match transcription_result.status.as_str() {
+                "completed" => {
+                    info!("Transcription completed!");
+                    return Ok(transcription_result);
+                }
+                "error" => {
+                    error!("Transcription with assemblyAI failed - id: {:?} - {:?}", transcription_result.id, transcription_result.error);
+                    return Err(anyhow::Error::msg(format!("Transcription failed: {}", transcription_result.error.unwrap_or_default())));
+                }
+                "queued" => progress(TranscriptionStage::Queued, None),
+                _ => progress(TranscriptionStage::Processing, None),
+            }
+            info!("Waiting for AssemblyAI to complete transcription...");
+            cancellable_sleep(POLLING_INTERVAL, cancel).await?;
+        }
+    }
+
+    /// Timed sentences of a completed transcript, with the speaker label when available
+    pub async fn sentences(&self, transcript_id: &String, cancel: &CancelFlag) -> Result<Vec<TranscriptSegment>, Error> {
+        let endpoint = format!("{}/transcript/{}/sentences", BASE_URL, transcript_id);
+        let response = self.send_with_retry(cancel, || {
+            Ok(self.client
+                .get(&endpoint)
+                .header("authorization", self.api_token.clone()))
+        }).await?;
+
+        if response.status() != StatusCode::OK {
+            error!("Fetching sentences failed with status code: {:?} - {:?}", response.status(),  response.text().await?);
+            return Err(anyhow::Error::msg("Failed to fetch the timed transcript"));
+        }
+        let sentences = response.json::<SentencesResponse>().await?.sentences;
+        return Ok(sentences);
+    }
+
+    /// Wait for the beeped audio to be generated
+    pub async fn redacted_audio_url(&self, transcript_id: &String, cancel: &CancelFlag) -> Result<String, Error> {
+        let endpoint = format!("{}/transcript/{}/redacted-audio", BASE_URL, transcript_id);
+        loop {
+            let response = self.send_with_retry(cancel, || {
+                Ok(self.client
+                    .get(&endpoint)
+                    .header("authorization", self.api_token.clone()))
+            }).await?;
+
+            if response.status() != StatusCode::OK && response.status() != StatusCode::ACCEPTED {
+                error!("Fetching redacted audio failed with status code: {:?} - {:?}", response.status(),  response.text().await?);
+                return Err(anyhow::Error::msg("Failed to fetch the redacted audio"));
+            }
+            if response.status() == StatusCode::OK {
+                let redacted_audio = response.json::<RedactedAudioResponse>().await?;
+                if let (true, Some(url)) = (redacted_audio.status == "redacted_audio_ready", redacted_audio.redacted_audio_url) {
+                    return Ok(url);
+                }
+            }
+            info!("Waiting for AssemblyAI to redact the audio...");
+            cancellable_sleep(POLLING_INTERVAL, cancel).await?;
+        }
+    }
+
+    /// Download a file and replace `path` once it is complete
+    pub async fn download(&self, url: &String, path: &String, cancel: &CancelFlag) -> Result<(), Error> {
+        let response = self.send_with_retry(cancel, || Ok(self.client.get(url))).await?;
+        if response.status() != StatusCode::OK {
+            return Err(anyhow::anyhow!("Download failed with status code: {:?}", response.status()));
+        }
+        let tmp_path = format!("{}.download", path);
+        let mut file = tokio::fs::File::create(&tmp_path).await?;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            check_cancelled(cancel)?;
+            file.write_all(&chunk?).await?;
+        }
+        file.flush().await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+        return Ok(());
+    }
+
+    /// Retry with an exponential backoff on network errors, 5xx and 429
+    async fn send_with_retry<F>(&self, cancel: &CancelFlag, build_request: F) -> Result<Response, Error>
+    where F: Fn() -> Result<RequestBuilder, Error> {
+        let mut delay = self.retry_policy.initial_delay;
+        let mut attempt = 0;
+        loop {
+            check_cancelled(cancel)?;
+            let result = build_request()?.send().await;
+            // A cancelled upload surfaces as a body error
+            check_cancelled(cancel)?;
+            let is_transient = match &result {
+                Ok(response) => is_transient_status(response.status()),
+                Err(error) => error.is_timeout() || error.is_connect() || error.is_request(),
+            };
+            if !is_transient || attempt >= self.retry_policy.max_retries {
+                return result.map_err(|error| error.into());
+            }
+            attempt += 1;
+            match &result {
+                Ok(response) => warn!("[AssemblyAI] Transient error {} - retry {}/{} in {:?}", response.status(), attempt, self.retry_policy.max_retries, delay),
+                Err(error) => warn!("[AssemblyAI] Transient error {:?} - retry {}/{} in {:?}", error, attempt, self.retry_policy.max_retries, delay),
+            }
+            cancellable_sleep(delay, cancel).await?;
+            delay = min(delay * 2, self.retry_policy.max_delay);
+        }
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    return status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::REQUEST_TIMEOUT;
+}
+
+fn check_cancelled(cancel: &CancelFlag) -> Result<(), Error> {
+    if cancel.load(Ordering::SeqCst) {
+        return Err(TranscriptionCancelled.into());
+    }
+    return Ok(());
+}
+
+async fn cancellable_sleep(duration: Duration, cancel: &CancelFlag) -> Result<(), Error> {
+    let step = Duration::from_millis(250);
+    let mut elapsed = Duration::ZERO;
+    while elapsed < duration {
+        check_cancelled(cancel)?;
+        tokio::time::sleep(step).await;
+        elapsed += step;
+    }
+    return check_cancelled(cancel);
+}
+
+/// Read the audio file chunk by chunk, instead of loading it in memory
+fn file_body(audio_path: &String, progress: ProgressCallback, cancel: CancelFlag) -> Result<Body, Error> {
+    let file = match std::fs::File::open(audio_path) {
+        Ok(file) => file,
+        Err(error) => {
+            error!("Error opening audio file: {:?}", error);
+            return Err(anyhow::Error::msg("Transcription failed, cannot find the associated audio"));
+        }
+    };
+    let total_size = file.metadata()?.len().max(1);
+    let file = tokio::fs::File::from_std(file);
+
+    let stream = stream::try_unfold((file, 0_u64), move |(mut file, sent)| {
+        let progress = progress.clone();
+        let cancel = cancel.clone();
+        async move {
+            if cancel.load(Ordering::SeqCst) {
+                return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, TranscriptionCancelled));
+            }
+            let mut buffer = vec![0_u8; UPLOAD_CHUNK_SIZE];
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                return Ok(None);
+            }
+            buffer.truncate(read);
+            let sent = sent + read as u64;
+            progress(TranscriptionStage::Uploading, Some(sent as f32 / total_size as f32));
+            Ok(Some((buffer, (file, sent))))
+        }
+    });
+    return Ok(Body::wrap_stream(stream));
+}
@@ -0,0 +1,174 @@
+/*
+    * Correction rules
+    * Find & replace applied to the transcript after the transcription, before it is saved
+*/
+use anyhow::Error;
+use log::warn;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::Serialize;
+use ts_rs::TS;
+use crate::model::CorrectionRule;
+
+const CONTEXT_LENGTH: usize = 40;
+
+#[derive(Clone, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct CorrectionPreview {
+    pub rule_index: usize,
+    pub matched: String,
+    pub replacement: String,
+    pub context: String,
+}
+
+fn build_regex(rule: &CorrectionRule) -> Result<Regex, Error> {
+    let pattern = match rule.is_regex {
+        true => rule.find.clone(),
+        false => regex::escape(&rule.find),
+    };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!rule.case_sensitive)
+        .build();
+    match regex {
+        Ok(regex) => Ok(regex),
+        Err(error) => Err(anyhow::anyhow!("Invalid correction rule `{}`: {}", rule.find, error)),
+    }
+}
+
+fn applies_to(rule: &CorrectionRule, company_id: &str) -> bool {
+    match &rule.company_id {
+        Some(rule_company_id) if !rule_company_id.is_empty() => rule_company_id == company_id,
+        _ => true,
+    }
+}
+
+pub fn validate_rules(rules: &Vec<CorrectionRule>) -> Result<(), Error> {
+    for rule in rules {
+        if rule.find.is_empty() {
+            return Err(anyhow::anyhow!("Correction rule with an empty search"));
+        }
+        build_regex(rule)?;
+    }
+    return Ok(());
+}
+
+fn replace(regex: &Regex, rule: &CorrectionRule, text: &str) -> String {
+    match rule.is_regex {
+        // Capture groups ($1, ${name}) are only expanded for regex rules
+        true => regex.replace_all(text, rule.replace.as_str()).to_string(),
+        false => regex.replace_all(text, NoExpand(&rule.replace)).to_string(),
+    }
+}
+
+/// Apply the rules in order - Invalid rules are skipped
+pub fn apply_rules(text: &str, rules: &Vec<CorrectionRule>, company_id: &str) -> String {
+    let mut text = text.to_string();
+    for rule in rules.iter().filter(|rule| applies_to(rule, company_id)) {
+        match build_regex(rule) {
+            Ok(regex) => text = replace(&regex, rule, &text),
+            Err(error) => warn!("[Correction] Rule skipped: {:?}", error),
+        }
+    }
+    return text;
+}
+
+/// List the replacements that would fire, without modifying the text
+pub fn preview_rules(text: &str, rules: &Vec<CorrectionRule>, company_id: &str) -> Result<Vec<CorrectionPreview>, Error> {
+    let mut previews = Vec::new();
+    let mut text = text.to_string();
+    for (rule_index, rule) in rules.iter().enumerate() {
+        if !applies_to(rule, company_id) {
+            continue;
+        }
+        let regex = build_regex(rule)?;
+        for captures in regex.captures_iter(&text) {
+            let matched = captures.get(0).unwrap();
+            let replacement = match rule.is_regex {
+                true => {
+                    let mut replacement = String::new();
+                    captures.expand(&rule.replace, &mut replacement);
+                    replacement
+                },
+                false => rule.replace.clone(),
+            };
+            previews.push(CorrectionPreview {
+                rule_index,
+                matched: matched.as_str().to_string(),
+                replacement,
+                context: context(&text, matched.start(), matched.end()),
+            });
+        }
+        // Next rules see the text corrected by the previous ones
+        text = replace(&regex, rule, &text);
+    }
+    return Ok(previews);
+}
+
+fn context(text: &str, start: usize, end: usize) -> String {
+    let mut context_start = start.saturating_sub(CONTEXT_LENGTH);
+    while !text.is_char_boundary(context_start) {
+        context_start -= 1;
+    }
+    let mut context_end = (end + CONTEXT_LENGTH).min(text.len());
+    while !text.is_char_boundary(context_end) {
+        context_end += 1;
+    }
+    return text[context_start..context_end].to_string();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(find: &str, replace: &str, is_regex: bool, case_sensitive: bool, company_id: Option<&str>) -> CorrectionRule {
+        return CorrectionRule {
+            find: find.to_string(),
+            replace: replace.to_string(),
+            is_regex,
+            case_sensitive,
+            company_id: company_id.map(|company_id| company_id.to_string()),
+        };
+    }
+
+    #[test]
+    fn replaces_plain_text() {
+        let rules = vec![rule("watsen", "Watson", false, false, None)];
+        assert_eq!(apply_rules("Hello Watsen and WATSEN", &rules, ""), "Hello Watson and Watson");
+        let rules = vec![rule("watsen", "Watson", false, true, None)];
+        assert_eq!(apply_rules("Hello Watsen and watsen", &rules, ""), "Hello Watsen and Watson");
+    }
+
+    #[test]
+    fn expands_groups_of_regex_rules_only() {
+        let rules = vec![rule(r"(\d+) k\b", "${1}000", true, false, None)];
+        assert_eq!(apply_rules("They raised 5 k today", &rules, ""), "They raised 5000 today");
+        let rules = vec![rule("price", "$1", false, false, None)];
+        assert_eq!(apply_rules("The price", &rules, ""), "The $1");
+        // Regex characters of plain rules are literal
+        let rules = vec![rule("a.b", "x", false, false, None)];
+        assert_eq!(apply_rules("a.b acb", &rules, ""), "x acb");
+    }
+
+    #[test]
+    fn applies_company_rules_to_their_company() {
+        let rules = vec![rule("acme", "ACME", false, false, Some("42"))];
+        assert_eq!(apply_rules("acme", &rules, "7"), "acme");
+        assert_eq!(apply_rules("acme", &rules, "42"), "ACME");
+    }
+
+    #[test]
+    fn skips_invalid_rules() {
+        let rules = vec![rule("(", "x", true, false, None), rule("a", "b", false, false, None)];
+        assert!(validate_rules(&rules).is_err());
+        assert_eq!(apply_rules("a", &rules, ""), "b");
+    }
+
+    #[test]
+    fn previews_rules_in_order() {
+        let rules = vec![rule("a", "b", false, false, None), rule("b", "c", false, false, None)];
+        let previews = preview_rules("a", &rules, "").unwrap();
+        assert_eq!(previews.len(), 2);
+        assert_eq!(previews[1].rule_index, 1);
+        assert_eq!(previews[1].matched, "b");
+        assert_eq!(previews[1].replacement, "c");
+    }
+}
@@ -0,0 +1,217 @@
+/*
+    * Transcription jobs
+    * The provider job id is persisted as soon as it is known, so a transcription
+    * can be resumed on startup instead of uploading the audio again.
+*/
+use std::sync::Arc;
+use anyhow::Error;
+use log::{error, info, warn};
+use tauri::{AppHandle, Manager};
+use chrono::Utc;
+use crate::model::{AppliedRedaction, RedactionMode, TranscriptionJob, UsageEntry, UsageKind, UsageLedger};
+use crate::{MeetingControllerState, SettingControllerState, TranscriptionJobControllerState};
+use crate::summarizer::metadata;
+use super::assemblyai::AssemblyAIClient;
+use super::{correction, redaction, vocabulary};
+use super::{CancelFlag, ProgressCallback, TranscriptionCancelled, TranscriptionOptions, TranscriptionProgress};
+
+
+pub fn resume_pending_jobs(app: AppHandle) {
+    let pending = {
+        let job_controller = app.state::<TranscriptionJobControllerState>();
+        let job_controller = job_controller.0.lock().unwrap();
+        job_controller.pending()
+    };
+    for job in pending {
+        info!("[Transcription] Resuming job {} - meeting {}", job.get_uuid(), job.meeting_id);
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = run_job(app, job.get_uuid()).await;
+        });
+    }
+}
+
+pub async fn run_job(app: AppHandle, job_id: String) -> Result<String, Error> {
+    let cancel = {
+        let job_controller = app.state::<TranscriptionJobControllerState>();
+        let mut job_controller = job_controller.0.lock().unwrap();
+        job_controller.register_cancel_flag(job_id.clone())
+    };
+    let result = process_job(&app, job_id.clone(), &cancel).await;
+    if result.is_ok() {
+        generate_metadata(&app, job_id.clone()).await;
+    }
+    let job = {
+        let job_controller = app.state::<TranscriptionJobControllerState>();
+        let mut job_controller = job_controller.0.lock().unwrap();
+        job_controller.release_cancel_flag(job_id.clone());
+        match &result {
+            Ok(_) => job_controller.complete(job_id.clone()),
+            Err(error) if error.is::<TranscriptionCancelled>() => job_controller.cancelled(job_id.clone()),
+            Err(error) => job_controller.fail(job_id.clone(), error.to_string()),
+        }
+    };
+    match job {
+        Ok(job) => { let _ = app.emit_all("TRANSCRIPTION_JOB", job); },
+        Err(error) => error!("[Transcription] Failed to update job {}: {:?}", job_id, error),
+    }
+    return result;
+}
+
+async fn process_job(app: &AppHandle, job_id: String, cancel: &CancelFlag) -> Result<String, Error> {
+    let job: TranscriptionJob = {
+        let job_controller = app.state::<TranscriptionJobControllerState>();
+        let job_controller = job_controller.0.lock().unwrap();
+        job_controller.get(job_id.clone())?
+    };
+
+    let client = AssemblyAIClient::from_setting();
+    let progress_app = app.clone();
+    let progress_job_id = job_id.clone();
+    let progress: ProgressCallback = Arc::new(move |stage, progress| {
+        let _ = progress_app.emit_all("TRANSCRIPTION_PROGRESS", TranscriptionProgress {
+            job_id: progress_job_id.clone(),
+            stage,
+            progress,
+        });
+    });
+
+    let (provider_job_id, redaction) = match job.provider_job_id {
+        Some(provider_job_id) => (provider_job_id, job.redaction.clone()),
+        None => {
+            // Resumed jobs are already paid - Only new uploads are blocked
+            UsageLedger::check_budget(UsageKind::Transcription)?;
+            let setting = {
+                let setting_controller = app.state::<SettingControllerState>();
+                let setting_controller = setting_controller.0.lock().unwrap();
+                setting_controller.get_setting()?
+            };
+            let options = TranscriptionOptions {
+                language: job.language.clone(),
+                word_boost: meeting_vocabulary(app, job.meeting_id.clone()).await,
+                redaction: redaction::active_policy(&setting),
+            };
+            let provider_job_id = client.submit(&job.audio_path, &options, progress.clone(), cancel).await?;
+            let job_controller = app.state::<TranscriptionJobControllerState>();
+            let mut job_controller = job_controller.0.lock().unwrap();
+            let job = job_controller.set_provider_job_id(job_id.clone(), provider_job_id.clone(), options.redaction.clone())?;
+            let _ = app.emit_all("TRANSCRIPTION_JOB", job);
+            (provider_job_id, options.redaction)
+        }
+    };
+
+    let transcript_response = client.poll(&provider_job_id, progress, cancel).await?;
+    UsageLedger::record(UsageEntry::transcription(Some(job.meeting_id.clone()), "AssemblyAI", transcript_response.audio_duration.unwrap_or_default()));
+    let mut transcript = transcript_response.text.clone().unwrap_or_default();
+    let mut segments = match client.sentences(&provider_job_id, cancel).await {
+        Ok(segments) => Some(segments),
+        Err(error) => {
+            warn!("[Transcription] No timed transcript for job {}: {:?}", job_id, error);
+            None
+        }
+    };
+    let (correction_rules, company_id) = {
+        let setting_controller = app.state::<SettingControllerState>();
+        let setting_controller = setting_controller.0.lock().unwrap();
+        let meeting_controller = app.state::<MeetingControllerState>();
+        let meeting_controller = meeting_controller.0.lock().unwrap();
+        let company_id = match meeting_controller.get(job.meeting_id.clone()) {
+            Ok(meeting) => meeting.company_id,
+            Err(_) => String::new(),
+        };
+        (setting_controller.get_setting()?.correction_rules.unwrap_or_default(), company_id)
+    };
+
+    transcript = correction::apply_rules(&transcript, &correction_rules, &company_id);
+    for segment in segments.iter_mut().flatten() {
+        segment.text = correction::apply_rules(&segment.text, &correction_rules, &company_id);
+    }
+
+    // Redaction - The provider already masked the transcript in `Provider` mode
+    let applied_redaction = match redaction {
+        Some(policy) => {
+            if policy.mode == RedactionMode::Local {
+                transcript = redaction::redact_text(&transcript, &policy.entity_types);
+                for segment in segments.iter_mut().flatten() {
+                    segment.text = redaction::redact_text(&segment.text, &policy.entity_types);
+                }
+            }
+            let audio_redacted = match policy.redact_audio {
+                true => {
+                    let words = transcript_response.words.clone().unwrap_or_default();
+                    match redaction::redact_audio(&client, &provider_job_id, &policy, &job.audio_path, &words, cancel).await {
+                        Ok(_) => true,
+                        Err(error) => {
+                            error!("[Transcription] Audio redaction failed for job {}: {:?}", job_id, error);
+                            false
+                        }
+                    }
+                },
+                false => false,
+            };
+            Some(AppliedRedaction {
+                policy,
+                audio_redacted,
+                applied_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            })
+        },
+        None => None,
+    };
+
+    // Attach the transcript to the meeting
+    let meeting_controller = app.state::<MeetingControllerState>();
+    let mut meeting_controller = meeting_controller.0.lock().unwrap();
+    match meeting_controller.get(job.meeting_id.clone()) {
+        Ok(mut meeting) => {
+            meeting.transcript = transcript.clone();
+            meeting.segments = segments;
+            meeting.detected_language = transcript_response.language_code.clone();
+            meeting.language_confidence = transcript_response.language_confidence;
+            meeting.redaction = applied_redaction;
+            meeting_controller.update(meeting)?;
+        },
+        Err(error) => {
+            warn!("[Transcription] Meeting {} not found, transcript not attached: {:?}", job.meeting_id, error);
+        }
+    }
+    return Ok(transcript);
+}
+
+/// Optional step - A failure doesn't fail the transcription
+async fn generate_metadata(app: &AppHandle, job_id: String) {
+    let (enabled, meeting_id) = {
+        let setting_controller = app.state::<SettingControllerState>();
+        let setting_controller = setting_controller.0.lock().unwrap();
+        let job_controller = app.state::<TranscriptionJobControllerState>();
+        let job_controller = job_controller.0.lock().unwrap();
+        let enabled = setting_controller.get_setting().map(|setting| setting.auto_metadata.unwrap_or(false)).unwrap_or(false);
+        (enabled, job_controller.get(job_id).map(|job| job.meeting_id))
+    };
+    let meeting_id = match (enabled, meeting_id) {
+        (true, Ok(meeting_id)) => meeting_id,
+        _ => return,
+    };
+    let app = app.clone();
+    // LLM client is blocking
+    let result = tauri::async_runtime::spawn_blocking(move || metadata::generate_and_apply(&app, meeting_id)).await;
+    match result {
+        Ok(Ok(metadata)) => info!("[Transcription] Metadata generated for meeting {}", metadata.meeting_id),
+        Ok(Err(error)) => warn!("[Transcription] Metadata generation failed: {:?}", error),
+        Err(error) => warn!("[Transcription] Metadata generation failed: {:?}", error),
+    }
+}
+
+async fn meeting_vocabulary(app: &AppHandle, meeting_id: String) -> Vec<String> {
+    let meeting = {
+        let meeting_controller = app.state::<MeetingControllerState>();
+        let meeting_controller = meeting_controller.0.lock().unwrap();
+        meeting_controller.get(meeting_id)
+    };
+    match meeting {
+        // Affinity client is blocking
+        Ok(meeting) => tauri::async_runtime::spawn_blocking(move || vocabulary::meeting_vocabulary(&meeting))
+            .await
+            .unwrap_or_else(|_| vocabulary::glossary_vocabulary()),
+        Err(_) => vocabulary::glossary_vocabulary(),
+    }
+}
@@ -0,0 +1,196 @@
+/*
+    * Languages supported by AssemblyAI
+    * https://www.assemblyai.com/docs/concepts/supported-languages
+*/
+use log::warn;
+use serde::Serialize;
+use ts_rs::TS;
+
+#[derive(Clone, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct TranscriptionLanguage {
+    pub code: String,
+    pub name: String,
+}
+
+pub enum LanguageOption {
+    AutoDetect,
+    Code(String),
+}
+
+// (code, name, other accepted names)
+const ASSEMBLYAI_LANGUAGES: &[(&str, &str, &[&str])] = &[
+    ("en", "English (global)", &["english", "anglais"]),
+    ("en_us", "English (US)", &[]),
+    ("en_uk", "English (UK)", &["en_gb"]),
+    ("en_au", "English (Australian)", &[]),
+    ("es", "Spanish", &["spanish", "español", "espanol", "espagnol"]),
+    ("fr", "French", &["french", "francais", "français"]),
+    ("de", "German", &["german", "deutsch", "allemand"]),
+    ("it", "Italian", &["italian", "italiano", "italien"]),
+    ("pt", "Portuguese", &["portuguese", "português", "portugues"]),
+    ("nl", "Dutch", &["dutch", "nederlands"]),
+    ("hi", "Hindi", &["hindi"]),
+    ("ja", "Japanese", &["japanese", "日本語"]),
+    ("zh", "Chinese", &["chinese", "中文"]),
+    ("fi", "Finnish", &["finnish", "suomi"]),
+    ("ko", "Korean", &["korean", "한국어"]),
+    ("pl", "Polish", &["polish", "polski"]),
+    ("ru", "Russian", &["russian", "русский"]),
+    ("tr", "Turkish", &["turkish", "türkçe"]),
+    ("uk", "Ukrainian", &["ukrainian", "українська"]),
+    ("vi", "Vietnamese", &["vietnamese", "tiếng việt"]),
+    ("af", "Afrikaans", &["afrikaans"]),
+    ("sq", "Albanian", &["albanian"]),
+    ("am", "Amharic", &["amharic"]),
+    ("ar", "Arabic", &["arabic", "العربية"]),
+    ("hy", "Armenian", &["armenian"]),
+    ("as", "Assamese", &["assamese"]),
+    ("az", "Azerbaijani", &["azerbaijani"]),
+    ("ba", "Bashkir", &["bashkir"]),
+    ("eu", "Basque", &["basque", "euskara"]),
+    ("be", "Belarusian", &["belarusian"]),
+    ("bn", "Bengali", &["bengali"]),
+    ("bs", "Bosnian", &["bosnian"]),
+    ("br", "Breton", &["breton"]),
+    ("bg", "Bulgarian", &["bulgarian"]),
+    ("my", "Burmese", &["burmese"]),
+    ("ca", "Catalan", &["catalan", "català"]),
+    ("hr", "Croatian", &["croatian", "hrvatski"]),
+    ("cs", "Czech", &["czech", "čeština"]),
+    ("da", "Danish", &["danish", "dansk"]),
+    ("et", "Estonian", &["estonian", "eesti"]),
+    ("fo", "Faroese", &["faroese"]),
+    ("gl", "Galician", &["galician", "galego"]),
+    ("ka", "Georgian", &["georgian"]),
+    ("el", "Greek", &["greek", "ελληνικά"]),
+    ("gu", "Gujarati", &["gujarati"]),
+    ("ht", "Haitian", &["haitian", "haitian creole"]),
+    ("ha", "Hausa", &["hausa"]),
+    ("haw", "Hawaiian", &["hawaiian"]),
+    ("he", "Hebrew", &["hebrew", "עברית"]),
+    ("hu", "Hungarian", &["hungarian", "magyar"]),
+    ("is", "Icelandic", &["icelandic", "íslenska"]),
+    ("id", "Indonesian", &["indonesian", "bahasa indonesia"]),
+    ("jw", "Javanese", &["javanese"]),
+    ("kn", "Kannada", &["kannada"]),
+    ("kk", "Kazakh", &["kazakh"]),
+    ("km", "Khmer", &["khmer"]),
+    ("lo", "Lao", &["lao"]),
+    ("la", "Latin", &["latin"]),
+    ("lv", "Latvian", &["latvian"]),
+    ("ln", "Lingala", &["lingala"]),
+    ("lt", "Lithuanian", &["lithuanian"]),
+    ("lb", "Luxembourgish", &["luxembourgish"]),
+    ("mk", "Macedonian", &["macedonian"]),
+    ("mg", "Malagasy", &["malagasy"]),
+    ("ms", "Malay", &["malay", "bahasa melayu"]),
+    ("ml", "Malayalam", &["malayalam"]),
+    ("mt", "Maltese", &["maltese"]),
+    ("mi", "Maori", &["maori"]),
+    ("mr", "Marathi", &["marathi"]),
+    ("mn", "Mongolian", &["mongolian"]),
+    ("ne", "Nepali", &["nepali"]),
+    ("no", "Norwegian", &["norwegian", "norsk"]),
+    ("nn", "Norwegian Nynorsk", &["nynorsk"]),
+    ("oc", "Occitan", &["occitan"]),
+    ("pa", "Panjabi", &["panjabi", "punjabi"]),
+    ("ps", "Pashto", &["pashto"]),
+    ("fa", "Persian", &["persian", "farsi"]),
+    ("sa", "Sanskrit", &["sanskrit"]),
+    ("sr", "Serbian", &["serbian"]),
+    ("sn", "Shona", &["shona"]),
+    ("sd", "Sindhi", &["sindhi"]),
+    ("si", "Sinhala", &["sinhala"]),
+    ("sk", "Slovak", &["slovak"]),
+    ("sl", "Slovenian", &["slovenian"]),
+    ("so", "Somali", &["somali"]),
+    ("su", "Sundanese", &["sundanese"]),
+    ("sw", "Swahili", &["swahili"]),
+    ("sv", "Swedish", &["swedish", "svenska"]),
+    ("tl", "Tagalog", &["tagalog", "filipino"]),
+    ("tg", "Tajik", &["tajik"]),
+    ("ta", "Tamil", &["tamil"]),
+    ("tt", "Tatar", &["tatar"]),
+    ("te", "Telugu", &["telugu"]),
+    ("th", "Thai", &["thai", "ไทย"]),
+    ("bo", "Tibetan", &["tibetan"]),
+    ("tk", "Turkmen", &["turkmen"]),
+    ("ur", "Urdu", &["urdu"]),
+    ("uz", "Uzbek", &["uzbek"]),
+    ("cy", "Welsh", &["welsh", "cymraeg"]),
+    ("yi", "Yiddish", &["yiddish"]),
+    ("yo", "Yoruba", &["yoruba"]),
+];
+
+pub fn supported_languages() -> Vec<TranscriptionLanguage> {
+    return ASSEMBLYAI_LANGUAGES.iter().map(|(code, name, _)| TranscriptionLanguage {
+        code: code.to_string(),
+        name: name.to_string(),
+    }).collect();
+}
+
+pub fn language_name(code: &str) -> Option<String> {
+    return ASSEMBLYAI_LANGUAGES.iter()
+        .find(|(language_code, _, _)| *language_code == code)
+        .map(|(_, name, _)| name.to_string());
+}
+
+/// Map the language selected by the user to an AssemblyAI language code
+/// -> Unknown languages are auto-detected instead of being forced to English
+pub fn resolve_language(language: Option<String>) -> LanguageOption {
+    let language = match language {
+        Some(language) => language.trim().to_lowercase().replace('-', "_"),
+        None => return LanguageOption::AutoDetect,
+    };
+    match language.as_str() {
+        "" | "auto" | "auto_detect" | "detect" => return LanguageOption::AutoDetect,
+        _ => (),
+    }
+    for (code, name, aliases) in ASSEMBLYAI_LANGUAGES {
+        if *code == language || name.to_lowercase() == language || aliases.contains(&language.as_str()) {
+            return LanguageOption::Code(code.to_string());
+        }
+    }
+    warn!("[Transcription] Unsupported language {} - Falling back on language detection", language);
+    return LanguageOption::AutoDetect;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(language: Option<&str>) -> Option<String> {
+        match resolve_language(language.map(|language| language.to_string())) {
+            LanguageOption::AutoDetect => None,
+            LanguageOption::Code(code) => Some(code),
+        }
+    }
+
+    #[test]
+    fn detects_when_not_set() {
+        assert_eq!(code(None), None);
+        assert_eq!(code(Some("")), None);
+        assert_eq!(code(Some("Auto")), None);
+    }
+
+    #[test]
+    fn resolves_codes_names_and_aliases() {
+        assert_eq!(code(Some("En")), Some("en".to_string()));
+        assert_eq!(code(Some("en-US")), Some("en_us".to_string()));
+        assert_eq!(code(Some("Chinese")), Some("zh".to_string()));
+        assert_eq!(code(Some("Français")), Some("fr".to_string()));
+    }
+
+    #[test]
+    fn detects_unknown_languages() {
+        assert_eq!(code(Some("Klingon")), None);
+    }
+
+    #[test]
+    fn names_codes() {
+        assert_eq!(language_name("fr"), Some("French".to_string()));
+        assert_eq!(language_name("xx"), None);
+        assert!(supported_languages().iter().any(|language| language.code == "en"));
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use serde::Serialize;
+use ts_rs::TS;
+use crate::model::RedactionPolicy;
+
+pub mod assemblyai;
+pub mod correction;
+pub mod job;
+pub mod language;
+pub mod redaction;
+pub mod vocabulary;
+
+/// Shared flag used to cancel a running transcription
+pub type CancelFlag = Arc<AtomicBool>;
+
+/// Called by the provider client each time the transcription move forward
+/// -> progress is between 0 and 1 when it is known
+pub type ProgressCallback = Arc<dyn Fn(TranscriptionStage, Option<f32>) + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct TranscriptionOptions {
+    pub language: Option<String>,
+    pub word_boost: Vec<String>,
+    pub redaction: Option<RedactionPolicy>,
+}
+
+#[derive(Clone, Debug, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum TranscriptionStage {
+    Uploading,
+    Queued,
+    Processing,
+}
+
+#[derive(Clone, Debug, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct TranscriptionProgress {
+    pub job_id: String,
+    pub stage: TranscriptionStage,
+    pub progress: Option<f32>,
+}
+
+#[derive(Debug)]
+pub struct TranscriptionCancelled;
+
+impl std::fmt::Display for TranscriptionCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transcription cancelled")
+    }
+}
+
+impl std::error::Error for TranscriptionCancelled {}
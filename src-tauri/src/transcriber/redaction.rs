@@ -0,0 +1,239 @@
+/*
+    * PII redaction
+    * - Provider: AssemblyAI masks the transcript (and beeps the audio) itself
+    * - Local: Watson masks the transcript with its own rules, and mutes the audio with ffmpeg
+*/
+use anyhow::Error;
+use log::{info, warn};
+use regex::Regex;
+use crate::audio::bleep;
+use crate::model::{PiiEntityType, RedactionMode, RedactionPolicy, Setting};
+use super::assemblyai::{AssemblyAIClient, Word};
+use super::CancelFlag;
+
+impl PiiEntityType {
+    /// AssemblyAI `redact_pii_policies` value
+    pub fn provider_policy(&self) -> &'static str {
+        match self {
+            PiiEntityType::EmailAddress => "email_address",
+            PiiEntityType::PhoneNumber => "phone_number",
+            PiiEntityType::BankingInformation => "banking_information",
+            PiiEntityType::CreditCardNumber => "credit_card_number",
+            PiiEntityType::PersonName => "person_name",
+            PiiEntityType::Location => "location",
+        }
+    }
+
+    /// Same substitution as AssemblyAI `entity_name`, so both modes look alike
+    fn mask(&self) -> String {
+        return format!("[{}]", self.provider_policy().to_uppercase());
+    }
+
+    /// Local rules - Names and locations need a NER model, only the provider can redact them
+    /// When a pattern has a capture group, only the group is masked (the rest is context)
+    fn local_patterns(&self) -> Vec<&'static str> {
+        match self {
+            PiiEntityType::EmailAddress => vec![
+                r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+                r"(?i)\b[a-z0-9._-]+ at [a-z0-9-]+(?: dot [a-z0-9-]+)*? dot (?:com|net|org|io|co|ai|fr|ca|uk|de)\b",
+            ],
+            PiiEntityType::CreditCardNumber => vec![r"\b(?:\d[ -]?){12,18}\d\b"],
+            PiiEntityType::BankingInformation => vec![
+                r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,4})?\b", // IBAN
+                r"(?i)\b(?:account|acct|routing|sort code|compte|rib)(?: number| no\.?| num[eé]ro| #)?:?\s+(\d(?:[ -]?\d){5,16})\b", // Account number
+            ],
+            PiiEntityType::PhoneNumber => vec![
+                r"\+\d{1,3}(?:[ .-]?\(?\d{1,4}\)?){2,6}\b", // International
+                r"(?:\(\d{3}\)|\b\d{3})[ .-]?\d{3}[ .-]\d{4}\b", // North American
+                r"\b0\d(?:[ .-]?\d{2}){4}\b", // European, e.g. 06 12 34 56 78
+                r"\b0\d{2,4}[ .-]\d{3,4}[ .-]\d{3,4}\b", // European, e.g. 020 7946 0958
+            ],
+            PiiEntityType::PersonName | PiiEntityType::Location => vec![],
+        }
+    }
+
+    /// Reject the matches that only have the shape of the entity
+    fn is_valid(&self, found: &str) -> bool {
+        let digits: Vec<u32> = found.chars().filter_map(|c| c.to_digit(10)).collect();
+        match self {
+            PiiEntityType::CreditCardNumber => luhn(&digits),
+            PiiEntityType::PhoneNumber => digits.len() >= 8 && digits.len() <= 15,
+            _ => true,
+        }
+    }
+}
+
+fn luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits.iter().rev().enumerate().map(|(i, digit)| {
+        if i % 2 == 0 {
+            return *digit;
+        }
+        let doubled = digit * 2;
+        return if doubled > 9 { doubled - 9 } else { doubled };
+    }).sum();
+    return sum % 10 == 0;
+}
+
+struct Rule {
+    regex: Regex,
+    entity_type: PiiEntityType,
+}
+
+impl Rule {
+    /// Byte ranges to mask in the text
+    fn ranges(&self, text: &str) -> Vec<(usize, usize)> {
+        return self.regex.captures_iter(text)
+            .filter_map(|captures| captures.get(1).or(captures.get(0)))
+            .filter(|found| self.entity_type.is_valid(found.as_str()))
+            .map(|found| (found.start(), found.end()))
+            .collect();
+    }
+}
+
+pub fn active_policy(setting: &Setting) -> Option<RedactionPolicy> {
+    return setting.redaction.clone().filter(|policy| policy.enabled && !policy.entity_types.is_empty());
+}
+
+pub fn provider_policies(policy: &RedactionPolicy) -> Vec<&'static str> {
+    return policy.entity_types.iter().map(|entity_type| entity_type.provider_policy()).collect();
+}
+
+/// The most specific entities first, so a card number is not masked as a phone number
+fn local_rules(entity_types: &Vec<PiiEntityType>) -> Vec<Rule> {
+    let order = [
+        PiiEntityType::EmailAddress,
+        PiiEntityType::CreditCardNumber,
+        PiiEntityType::BankingInformation,
+        PiiEntityType::PhoneNumber,
+        PiiEntityType::PersonName,
+        PiiEntityType::Location,
+    ];
+    let mut rules = Vec::new();
+    for entity_type in order.iter().filter(|entity_type| entity_types.contains(entity_type)) {
+        let patterns = entity_type.local_patterns();
+        if patterns.is_empty() {
+            warn!("[Redaction] {:?} can only be redacted by the provider", entity_type);
+        }
+        for pattern in patterns {
+            rules.push(Rule { regex: Regex::new(pattern).unwrap(), entity_type: entity_type.clone() });
+        }
+    }
+    return rules;
+}
+
+pub fn redact_text(text: &str, entity_types: &Vec<PiiEntityType>) -> String {
+    let mut text = text.to_string();
+    for rule in local_rules(entity_types) {
+        let mask = rule.entity_type.mask();
+        // From the end, so the earlier ranges stay valid
+        for (start, end) in rule.ranges(&text).into_iter().rev() {
+            text.replace_range(start..end, &mask);
+        }
+    }
+    return text;
+}
+
+/// Time ranges (ms) of the words matching the local rules
+pub fn pii_spans(words: &Vec<Word>, entity_types: &Vec<PiiEntityType>) -> Vec<(i32, i32)> {
+    // Rebuild the text from the words, keeping track of where each word is
+    let mut text = String::new();
+    let mut word_ranges = Vec::new();
+    for word in words {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        word_ranges.push((text.len(), text.len() + word.text.len(), word.start, word.end));
+        text.push_str(&word.text);
+    }
+
+    let mut spans = Vec::new();
+    for rule in local_rules(entity_types) {
+        for (found_start, found_end) in rule.ranges(&text) {
+            let overlapping: Vec<&(usize, usize, i32, i32)> = word_ranges.iter()
+                .filter(|(start, end, _, _)| *start < found_end && *end > found_start)
+                .collect();
+            if let (Some(first), Some(last)) = (overlapping.first(), overlapping.last()) {
+                spans.push((first.2, last.3));
+            }
+        }
+    }
+    spans.sort();
+    return spans;
+}
+
+/// Replace the audio file by its redacted version
+pub async fn redact_audio(client: &AssemblyAIClient, transcript_id: &String, policy: &RedactionPolicy, audio_path: &String, words: &Vec<Word>, cancel: &CancelFlag) -> Result<(), Error> {
+    match policy.mode {
+        RedactionMode::Provider => {
+            let redacted_audio_url = client.redacted_audio_url(transcript_id, cancel).await?;
+            client.download(&redacted_audio_url, audio_path, cancel).await?;
+        },
+        RedactionMode::Local => {
+            let spans = pii_spans(words, &policy.entity_types);
+            if spans.is_empty() {
+                info!("[Redaction] Nothing to redact in the audio");
+                return Ok(());
+            }
+            let audio_path = audio_path.clone();
+            // ffmpeg is blocking
+            tauri::async_runtime::spawn_blocking(move || bleep::bleep_spans(&audio_path, &spans)).await??;
+        },
+    }
+    info!("[Redaction] Audio redacted - {}", audio_path);
+    return Ok(());
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> Vec<PiiEntityType> {
+        return vec![
+            PiiEntityType::EmailAddress,
+            PiiEntityType::CreditCardNumber,
+            PiiEntityType::BankingInformation,
+            PiiEntityType::PhoneNumber,
+        ];
+    }
+
+    #[test]
+    fn keeps_dates_amounts_and_years() {
+        let texts = [
+            "The board meets on 2024-03-15 and again on 15.03.2024.",
+            "Revenue grew from 2024 12 500 to 1 500 000 dollars.",
+            "We raised 12500000 in 2023, 25000000 in 2024.",
+            "Order 123456789 ships in Q3 2025.",
+            "Between 1999 and 2024 they hired 350 people.",
+        ];
+        for text in texts {
+            assert_eq!(redact_text(text, &all()), text);
+        }
+    }
+
+    #[test]
+    fn masks_phone_numbers() {
+        assert_eq!(redact_text("Call me at +33 6 12 34 56 78 tomorrow", &all()), "Call me at [PHONE_NUMBER] tomorrow");
+        assert_eq!(redact_text("Call me at (555) 123-4567", &all()), "Call me at [PHONE_NUMBER]");
+        assert_eq!(redact_text("Call me at 555-123-4567", &all()), "Call me at [PHONE_NUMBER]");
+        assert_eq!(redact_text("Mon numéro est 06 12 34 56 78", &all()), "Mon numéro est [PHONE_NUMBER]");
+        assert_eq!(redact_text("Our office is 020 7946 0958", &all()), "Our office is [PHONE_NUMBER]");
+    }
+
+    #[test]
+    fn masks_banking_information() {
+        assert_eq!(redact_text("IBAN FR76 3000 6000 0112 3456 7890 189", &all()), "IBAN [BANKING_INFORMATION]");
+        assert_eq!(redact_text("The account number 12345678 is closed", &all()), "The account number [BANKING_INFORMATION] is closed");
+    }
+
+    #[test]
+    fn masks_card_numbers_passing_luhn_only() {
+        assert_eq!(redact_text("Card 4111 1111 1111 1111", &all()), "Card [CREDIT_CARD_NUMBER]");
+        assert_eq!(redact_text("Reference 4111 1111 1111 1112", &all()), "Reference 4111 1111 1111 1112");
+    }
+
+    #[test]
+    fn masks_email_addresses() {
+        assert_eq!(redact_text("Write to jane.doe@example.com", &all()), "Write to [EMAIL_ADDRESS]");
+    }
+}
@@ -0,0 +1,76 @@
+/*
+    * Custom vocabulary sent to the provider to boost names and jargon
+    * - User glossary from the settings
+    * - Company name of the meeting, and organization & persons linked in Affinity
+*/
+use std::time::Duration;
+use log::warn;
+use crate::crm::affinity;
+use crate::model::{Meeting, Setting, SettingController};
+
+// AssemblyAI limits: 1000 terms of 6 words max
+const MAX_TERMS: usize = 1000;
+const MAX_WORDS_PER_TERM: usize = 6;
+const MAX_CRM_PERSONS: usize = 25;
+// The vocabulary is loaded before the upload -> A slow CRM must not hold the transcription
+const CRM_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const CRM_DEADLINE: Duration = Duration::from_secs(15);
+
+/// The vocabulary is optional -> Transcribe without it if the settings can't be read
+fn setting() -> Option<Setting> {
+    match SettingController::new(crate::model::SettingPath::Default).get_setting() {
+        Ok(setting) => Some(setting),
+        Err(error) => {
+            warn!("[Vocabulary] Cannot load the settings, no vocabulary: {:?}", error);
+            None
+        }
+    }
+}
+
+pub fn glossary_vocabulary() -> Vec<String> {
+    return match setting() {
+        Some(setting) => clean_terms(setting.glossary.unwrap_or_default()),
+        None => Vec::new(),
+    };
+}
+
+/// Blocking - Call Affinity when the meeting is linked to an organization
+pub fn meeting_vocabulary(meeting: &Meeting) -> Vec<String> {
+    let setting = match setting() {
+        Some(setting) => setting,
+        None => return Vec::new(),
+    };
+    let mut terms = setting.glossary.unwrap_or_default();
+    terms.push(meeting.company_name.clone());
+    if !meeting.company_id.is_empty() && !setting.affinity_api_token.is_empty() {
+        terms.extend(crm_vocabulary(&meeting.company_id));
+    }
+    return clean_terms(terms);
+}
+
+fn crm_vocabulary(company_id: &str) -> Vec<String> {
+    match affinity::organization_names(company_id, MAX_CRM_PERSONS, CRM_REQUEST_TIMEOUT, CRM_DEADLINE) {
+        Ok(names) => return names,
+        Err(error) => {
+            warn!("[Vocabulary] No Affinity vocabulary for organization {}: {:?}", company_id, error);
+            return Vec::new();
+        }
+    }
+}
+
+fn clean_terms(terms: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for term in terms {
+        let term = term.split_whitespace().collect::<Vec<&str>>().join(" ");
+        if term.is_empty() || term.split(' ').count() > MAX_WORDS_PER_TERM {
+            continue;
+        }
+        if !cleaned.iter().any(|existing| existing.to_lowercase() == term.to_lowercase()) {
+            cleaned.push(term);
+        }
+        if cleaned.len() >= MAX_TERMS {
+            break;
+        }
+    }
+    return cleaned;
+}
@@ -0,0 +1,131 @@
+/*
+    * Line diff between two Markdown texts
+    * Longest common subsequence on lines - Summaries & notes are short enough
+    * Blank lines separate Markdown blocks, they are part of the diff so a partial apply keeps the structure
+*/
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub enum DiffKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../src/bindings/")]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+/// Trailing spaces & repeated blank lines do not change the rendered Markdown
+pub fn split_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines().map(|line| line.trim_end()) {
+        let previous_blank = lines.last().map(|previous| previous.is_empty()).unwrap_or(true);
+        if !line.is_empty() || !previous_blank {
+            lines.push(line.to_string());
+        }
+    }
+    if lines.last().map(|line| line.is_empty()).unwrap_or(false) {
+        lines.pop();
+    }
+    return lines;
+}
+
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old = split_lines(old);
+    let new = split_lines(new);
+    // lcs[i][j] = length of the common subsequence of old[i..] & new[j..]
+    let mut lcs = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => std::cmp::max(lcs[i + 1][j], lcs[i][j + 1]),
+            };
+        }
+    }
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine { kind: DiffKind::Unchanged, text: old[i].clone() });
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine { kind: DiffKind::Removed, text: old[i].clone() });
+            i += 1;
+        } else {
+            diff.push(DiffLine { kind: DiffKind::Added, text: new[j].clone() });
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|text| DiffLine { kind: DiffKind::Removed, text: text.clone() }));
+    diff.extend(new[j..].iter().map(|text| DiffLine { kind: DiffKind::Added, text: text.clone() }));
+    return diff;
+}
+
+/// Rebuild the new text keeping only the accepted changes - Other lines stay as in the old text
+/// Blank lines are always kept, so an accepted block is never merged with its neighbour
+pub fn apply_diff(diff: &Vec<DiffLine>, accepted: &Vec<usize>) -> String {
+    let mut lines = Vec::new();
+    for (index, line) in diff.iter().enumerate() {
+        let keep = match line.kind {
+            _ if line.text.is_empty() => true,
+            DiffKind::Unchanged => true,
+            DiffKind::Added => accepted.contains(&index),
+            DiffKind::Removed => !accepted.contains(&index),
+        };
+        if keep {
+            lines.push(line.text.clone());
+        }
+    }
+    return split_lines(&lines.join("\n")).join("\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(diff: &Vec<DiffLine>) -> Vec<(DiffKind, &str)> {
+        return diff.iter().map(|line| (line.kind.clone(), line.text.as_str())).collect();
+    }
+
+    #[test]
+    fn splits_lines_and_collapses_blank_lines() {
+        assert_eq!(split_lines("a  \n\n\n\nb\n\n"), vec!["a", "", "b"]);
+        assert_eq!(split_lines("\n\na"), vec!["a"]);
+        assert!(split_lines("").is_empty());
+    }
+
+    #[test]
+    fn diffs_changed_lines() {
+        let diff = diff_lines("a\nb\nc", "a\nB\nc");
+        assert_eq!(kinds(&diff), vec![
+            (DiffKind::Unchanged, "a"),
+            (DiffKind::Removed, "b"),
+            (DiffKind::Added, "B"),
+            (DiffKind::Unchanged, "c"),
+        ]);
+    }
+
+    #[test]
+    fn applies_only_accepted_changes() {
+        let diff = diff_lines("a\nb\nc", "a\nB\nc");
+        assert_eq!(apply_diff(&diff, &vec![1, 2]), "a\nB\nc");
+        assert_eq!(apply_diff(&diff, &vec![]), "a\nb\nc");
+        assert_eq!(apply_diff(&diff, &vec![2]), "a\nb\nB\nc");
+    }
+
+    #[test]
+    fn keeps_blocks_separated() {
+        let diff = diff_lines("para one\n\npara two", "para one\n\nnew para\n\npara two");
+        assert_eq!(apply_diff(&diff, &vec![]), "para one\n\npara two");
+        let added = diff.iter().position(|line| line.kind == DiffKind::Added && line.text == "new para").unwrap();
+        assert_eq!(apply_diff(&diff, &vec![added]), "para one\n\nnew para\n\npara two");
+    }
+}
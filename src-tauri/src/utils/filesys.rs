@@ -1,7 +1,37 @@
 use dirs;
+use std::path::Path;
+#[cfg(any(target_family = "windows", target_os = "macos"))]
+use std::process::Command;
 
 pub fn local_data_dir_path() -> std::path::PathBuf {
     let home_dir = dirs::data_local_dir().unwrap();
     let base_path = home_dir.join("watson_data");
     return base_path;
+}
+
+/// Ids from the UI are used as file names -> Nothing that could point outside the data folder
+pub fn check_file_id(id: &str) -> Result<(), anyhow::Error> {
+    let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(anyhow::anyhow!("Invalid id `{}`", id));
+    }
+    return Ok(());
+}
+
+/// Open the folder containing the file for the user to find it
+pub fn reveal_in_file_manager(path: &Path) {
+    #[cfg(target_family = "windows")]
+    Command::new("explorer")
+    .args(["/select,", path.to_str().unwrap()]) // The comma after select is not a typo
+    .spawn()
+    .unwrap();
+
+    #[cfg(target_os = "macos")]
+    Command::new("open")
+    .args(["-R", path.to_str().unwrap()])
+    .spawn()
+    .unwrap();
+
+    #[cfg(not(any(target_family = "windows", target_os = "macos")))]
+    let _ = path;
 }
\ No newline at end of file
@@ -0,0 +1,86 @@
+/*
+    * Markdown summaries
+    * Summaries are stored as Markdown - HTML is only produced for display & CRM notes, always sanitized
+*/
+use pulldown_cmark::{html, Options, Parser};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Markdown -> sanitized HTML
+pub fn to_html(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    let mut output = String::new();
+    html::push_html(&mut output, Parser::new_ext(markdown, options));
+    return sanitize_html(&output);
+}
+
+/// Remove scripts, styles, event handlers & unknown tags
+pub fn sanitize_html(html: &str) -> String {
+    return ammonia::clean(html);
+}
+
+/// HTML -> Markdown - Sanitized first so nothing unsafe survives in the text
+pub fn from_html(html: &str) -> String {
+    return html2md::parse_html(&sanitize_html(html)).trim().to_string();
+}
+
+// Called on every meeting load & update -> Compiled once
+static BLOCK_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)</?(p|h[1-6]|ul|ol|li|div|br|table|strong|em|b|i)(\s[^>]*)?/?>").unwrap());
+static CODE_FENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)^```(?:markdown|md|html)?\s*\n(.*)\n```$").unwrap());
+
+/// Legacy summaries & some model answers are HTML
+pub fn looks_like_html(text: &str) -> bool {
+    return BLOCK_TAG.is_match(text);
+}
+
+/// Model answers are sometimes wrapped in a code block
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    match CODE_FENCE.captures(trimmed).and_then(|captures| captures.get(1)) {
+        Some(inner) => inner.as_str(),
+        None => trimmed,
+    }
+}
+
+pub fn ensure_markdown(text: &str) -> String {
+    let text = strip_code_fence(text);
+    match looks_like_html(text) {
+        true => from_html(text),
+        false => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_markdown() {
+        assert_eq!(ensure_markdown("# Title\n\n- item"), "# Title\n\n- item");
+        assert!(!looks_like_html("a < b and c > d"));
+    }
+
+    #[test]
+    fn strips_code_fences() {
+        assert_eq!(ensure_markdown("```markdown\n# Title\n\n- item\n```"), "# Title\n\n- item");
+        assert_eq!(ensure_markdown("  ```\n**bold**\n```  "), "**bold**");
+    }
+
+    #[test]
+    fn converts_html() {
+        let markdown = ensure_markdown("<h1>Title</h1><p>Some <strong>bold</strong> text</p>");
+        assert!(!looks_like_html(&markdown));
+        assert!(markdown.contains("Title"));
+        assert!(markdown.contains("**bold**"));
+    }
+
+    #[test]
+    fn renders_sanitized_html() {
+        let html = to_html("<script>alert(1)</script>\n\n**x**");
+        assert!(!html.contains("<script"));
+        assert!(html.contains("<strong>x</strong>"));
+    }
+}
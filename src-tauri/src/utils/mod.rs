@@ -1,3 +1,5 @@
+pub mod diff;
 pub mod filesys;
+pub mod markdown;
 pub mod session;
 pub mod event;
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface ActionItem { uuid: string, task: string, owner: string | null, due_date: string | null, source_quote: string | null, done: boolean, done_at: string | null, }
\ No newline at end of file
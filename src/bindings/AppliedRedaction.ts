@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RedactionPolicy } from "./RedactionPolicy";
+
+export interface AppliedRedaction { policy: RedactionPolicy, audio_redacted: boolean, applied_at: string, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DigestSource } from "./DigestSource";
+
+export interface CompanyDigest { company_id: string, company_name: string, digest: string, sources: Array<DigestSource>, model: string, created_at: string, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConversationMessage } from "./ConversationMessage";
+
+export interface Conversation { meeting_id: string, messages: Array<ConversationMessage>, }
\ No newline at end of file
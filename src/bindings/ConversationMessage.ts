@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConversationRole } from "./ConversationRole";
+
+export interface ConversationMessage { role: ConversationRole, content: string, quotes: Array<string>, created_at: string, }
\ No newline at end of file
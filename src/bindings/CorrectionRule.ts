@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface CorrectionRule { find: string, replace: string, is_regex: boolean, case_sensitive: boolean, company_id: string | null, }
\ No newline at end of file
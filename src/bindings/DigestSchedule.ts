@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface DigestSchedule { enabled: boolean, weekday: number, hour: number, period_days: number, }
\ No newline at end of file
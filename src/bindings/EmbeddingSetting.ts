@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EmbeddingSource } from "./EmbeddingSource";
+
+export interface EmbeddingSetting { source: EmbeddingSource, model: string | null, }
\ No newline at end of file
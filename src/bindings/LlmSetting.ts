@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LlmProviderKind } from "./LlmProviderKind";
+
+export interface LlmSetting { provider: LlmProviderKind, model: string, base_url: string | null, api_key: string | null, api_version: string | null, context_window: number | null, }
\ No newline at end of file
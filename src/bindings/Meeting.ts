@@ -1,4 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ActionItem } from "./ActionItem";
+import type { AppliedRedaction } from "./AppliedRedaction";
 import type { Chapter } from "./Chapter";
+import type { FollowUpDraft } from "./FollowUpDraft";
+import type { NoteRevision } from "./NoteRevision";
+import type { SummaryInfo } from "./SummaryInfo";
+import type { TranscriptSegment } from "./TranscriptSegment";
 
-export interface Meeting { uuid: string, title: string, company_name: string, company_id: string, prompt: string, summary: string, note: string, transcript: string, datetime: string, audio_path: string, published: boolean, publish_with_note: boolean | null, chapters: Array<Chapter>, }
\ No newline at end of file
+export interface Meeting { uuid: string, title: string, company_name: string, company_id: string, prompt: string, summary: string, note: string, transcript: string, datetime: string, audio_path: string, published: boolean, publish_with_note: boolean | null, chapters: Array<Chapter>, detected_language: string | null, language_confidence: number | null, segments: Array<TranscriptSegment> | null, redaction: AppliedRedaction | null, action_items: Array<ActionItem> | null, summary_info: SummaryInfo | null, note_revision: NoteRevision | null, participants: Array<string> | null, meeting_type: string | null, follow_up: FollowUpDraft | null, }
\ No newline at end of file
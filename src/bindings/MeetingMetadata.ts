@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface MeetingMetadata { meeting_id: string, title: string | null, company_name: string | null, company_id: string | null, participants: Array<string>, meeting_type: string | null, suggested_prompt: string | null, }
\ No newline at end of file
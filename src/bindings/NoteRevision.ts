@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffLine } from "./DiffLine";
+
+export interface NoteRevision { original: string, proposed: string, diff: Array<DiffLine>, model: string, created_at: string, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ActionItem } from "./ActionItem";
+
+export interface OpenActionItem { meeting_id: string, meeting_title: string, meeting_datetime: string, company_name: string, item: ActionItem, }
\ No newline at end of file
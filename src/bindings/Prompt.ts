@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export interface Prompt { name: string, prompt: string, }
\ No newline at end of file
+export interface Prompt { id: string | null, name: string, prompt: string, version: number | null, description: string | null, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface PromptRule { company_id: string | null, title_pattern: string | null, meeting_type: string | null, prompt: string, model: string | null, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PiiEntityType } from "./PiiEntityType";
+import type { RedactionMode } from "./RedactionMode";
+
+export interface RedactionPolicy { enabled: boolean, mode: RedactionMode, entity_types: Array<PiiEntityType>, redact_audio: boolean, }
\ No newline at end of file
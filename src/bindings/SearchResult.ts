@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PassageSource } from "./PassageSource";
+
+export interface SearchResult { meeting_id: string, meeting_title: string, meeting_datetime: string, source: PassageSource, passage: string, score: number, }
\ No newline at end of file
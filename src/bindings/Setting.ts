@@ -1,5 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CorrectionRule } from "./CorrectionRule";
+import type { DigestSchedule } from "./DigestSchedule";
+import type { EmbeddingSetting } from "./EmbeddingSetting";
+import type { LlmSetting } from "./LlmSetting";
 import type { ModelTurbo } from "./ModelTurbo";
 import type { Prompt } from "./Prompt";
+import type { PromptRule } from "./PromptRule";
+import type { RedactionPolicy } from "./RedactionPolicy";
+import type { UsageBudget } from "./UsageBudget";
 
-export interface Setting { uuid: string, assemblyai_api_token: string, openai_api_token: string, affinity_api_token: string, affinity_crm_list_id: string | null, prompts: Array<Prompt> | null, default_model: ModelTurbo | null, }
\ No newline at end of file
+export interface Setting { uuid: string, assemblyai_api_token: string, openai_api_token: string, affinity_api_token: string, affinity_crm_list_id: string | null, prompts: Array<Prompt> | null, default_model: ModelTurbo | null, llm: LlmSetting | null, glossary: Array<string> | null, correction_rules: Array<CorrectionRule> | null, redaction: RedactionPolicy | null, embedding: EmbeddingSetting | null, budget: UsageBudget | null, auto_metadata: boolean | null, follow_up_prompt: string | null, digest_schedule: DigestSchedule | null, prompt_rules: Array<PromptRule> | null, }
\ No newline at end of file
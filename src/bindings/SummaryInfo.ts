@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SummaryOrigin } from "./SummaryOrigin";
+
+export interface SummaryInfo { origin: SummaryOrigin, prompt: string | null, model: string | null, created_at: string, }
\ No newline at end of file
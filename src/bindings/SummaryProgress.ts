@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SummaryStage } from "./SummaryStage";
+
+export interface SummaryProgress { stream_id: string, meeting_id: string, stage: SummaryStage, step: number, total_steps: number, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface SummaryToken { stream_id: string, meeting_id: string, token: string, }
\ No newline at end of file
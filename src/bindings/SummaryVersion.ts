@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SummaryInfo } from "./SummaryInfo";
+
+export interface SummaryVersion { uuid: string, summary: string, info: SummaryInfo | null, replaced_at: string, }
\ No newline at end of file
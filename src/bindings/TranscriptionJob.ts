@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RedactionPolicy } from "./RedactionPolicy";
+import type { TranscriptionStatus } from "./TranscriptionStatus";
+
+export interface TranscriptionJob { uuid: string, meeting_id: string, audio_path: string, language: string | null, provider: string, provider_job_id: string | null, status: TranscriptionStatus, error: string | null, redaction: RedactionPolicy | null, created_at: string, updated_at: string, }
\ No newline at end of file
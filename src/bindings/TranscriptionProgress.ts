@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TranscriptionStage } from "./TranscriptionStage";
+
+export interface TranscriptionProgress { job_id: string, stage: TranscriptionStage, progress: number | null, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface UsageBudget { monthly_llm_cap: number | null, monthly_transcription_cap: number | null, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UsageKind } from "./UsageKind";
+import type { UsageOperation } from "./UsageOperation";
+
+export interface UsageEntry { uuid: string, meeting_id: string | null, kind: UsageKind, operation: UsageOperation, provider: string, model: string, prompt_tokens: number, completion_tokens: number, audio_seconds: number, estimated_cost: number, created_at: string, }
\ No newline at end of file
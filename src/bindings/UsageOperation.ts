@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UsageOperation = "Summary" | "ImproveNote" | "Metadata" | "FollowUp" | "CompanyDigest" | "ActionItems" | "Chat" | "Embedding" | "Transcription";
\ No newline at end of file
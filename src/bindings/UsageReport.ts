@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UsageTotal } from "./UsageTotal";
+
+export interface UsageReport { from: string, to: string, total: UsageTotal, by_provider: Array<UsageTotal>, by_meeting: Array<UsageTotal>, month_llm_cost: number, month_transcription_cost: number, monthly_llm_cap: number | null, monthly_transcription_cap: number | null, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface UsageTotal { key: string, prompt_tokens: number, completion_tokens: number, audio_minutes: number, estimated_cost: number, }
\ No newline at end of file
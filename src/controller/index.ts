@@ -7,6 +7,26 @@ import { NewMeetingNote } from '../bindings/NewMeetingNote.js';
 import { MeetingsRef } from '../bindings/MeetingsRef.js';
 import { AvailableDevices } from '../bindings/AvailableDevices.js';
 import { RecordingDevices } from '../bindings/RecordingDevices.js';
+import { TranscriptionJob } from '../bindings/TranscriptionJob.js';
+import { TranscriptionLanguage } from '../bindings/TranscriptionLanguage.js';
+import { CorrectionPreview } from '../bindings/CorrectionPreview.js';
+import { TranscriptFormat } from '../bindings/TranscriptFormat.js';
+import { ActionItem } from '../bindings/ActionItem.js';
+import { OpenActionItem } from '../bindings/OpenActionItem.js';
+import { PromptPreview } from '../bindings/PromptPreview.js';
+import { Conversation } from '../bindings/Conversation.js';
+import { ConversationMessage } from '../bindings/ConversationMessage.js';
+import { SearchResult } from '../bindings/SearchResult.js';
+import { SummaryVersion } from '../bindings/SummaryVersion.js';
+import { DiffLine } from '../bindings/DiffLine.js';
+import { UsageReport } from '../bindings/UsageReport.js';
+import { MeetingMetadata } from '../bindings/MeetingMetadata.js';
+import { FollowUpDraft } from '../bindings/FollowUpDraft.js';
+import { FollowUpFormat } from '../bindings/FollowUpFormat.js';
+import { CompanyDigest } from '../bindings/CompanyDigest.js';
+import { PeriodDigest } from '../bindings/PeriodDigest.js';
+import { PromptPackFormat } from '../bindings/PromptPackFormat.js';
+import { PromptImportReport } from '../bindings/PromptImportReport.js';
 
 
 class MeetingModelController {
@@ -21,12 +41,85 @@ class MeetingModelController {
       return ipc_invoke(`update_meeting`, { id: meeting.uuid, data: meetingForUpdate } ).then(res => res.data);
     }
 
-    async summarize(meeting: Meeting): Promise<ModelMutateResultData> { 
-      return ipc_invoke(`async_summarize_meeting`, { id: meeting.uuid } ).then(res => res.data);
+    // Tokens are sent with the SUMMARY_TOKEN event, tagged with stream_id
+    // force: generate again instead of returning the cached answer for the same transcript & prompt
+    async summarize(meeting: Meeting, stream_id?: string, force?: boolean): Promise<ModelMutateResultData> { 
+      return ipc_invoke(`async_summarize_meeting`, { id: meeting.uuid, stream_id: stream_id, force: force } ).then(res => res.data);
     }
 
-    async improve_note(meeting: Meeting): Promise<ModelMutateResultData> {
-      return ipc_invoke(`async_improve_note_meeting`, { id: meeting.uuid } ).then(res => res.data);
+    async improve_note(meeting: Meeting, stream_id?: string, force?: boolean): Promise<ModelMutateResultData> {
+      return ipc_invoke(`async_improve_note_meeting`, { id: meeting.uuid, stream_id: stream_id, force: force } ).then(res => res.data);
+    }
+
+    // lines: indexes of the diff lines of `meeting.note_revision` to apply - every change if not set
+    async accept_note_revision(meeting: Meeting, lines?: number[]): Promise<ModelMutateResultData> {
+      return ipc_invoke(`accept_note_revision`, { id: meeting.uuid, lines: lines }).then(res => res.data);
+    }
+
+    async reject_note_revision(meeting: Meeting): Promise<ModelMutateResultData> {
+      return ipc_invoke(`reject_note_revision`, { id: meeting.uuid }).then(res => res.data);
+    }
+
+    async list_summary_versions(meeting: Meeting): Promise<SummaryVersion[]> {
+      return ipc_invoke(`list_summary_versions`, { id: meeting.uuid }).then(res => res.data);
+    }
+
+    // Without version id, the current summary is used
+    async diff_summary_versions(meeting: Meeting, from?: string, to?: string): Promise<DiffLine[]> {
+      return ipc_invoke(`diff_summary_versions`, { id: meeting.uuid, from: from, to: to }).then(res => res.data);
+    }
+
+    async restore_summary_version(meeting: Meeting, version_id: string): Promise<ModelMutateResultData> {
+      return ipc_invoke(`restore_summary_version`, { id: meeting.uuid, version_id: version_id }).then(res => res.data);
+    }
+
+    async semantic_search(query: string, limit?: number): Promise<SearchResult[]> {
+      return ipc_invoke(`semantic_search_meetings`, { query: query, limit: limit }).then(res => res.data);
+    }
+
+    async rebuild_search_index(): Promise<ModelMutateResultData> {
+      return ipc_invoke(`rebuild_search_index`, {}).then(res => res.data);
+    }
+
+    async ask(meeting: Meeting, question: string): Promise<ConversationMessage> {
+      return ipc_invoke(`ask_meeting`, { id: meeting.uuid, question: question }).then(res => res.data);
+    }
+
+    async get_conversation(meeting: Meeting): Promise<Conversation> {
+      return ipc_invoke(`get_meeting_conversation`, { id: meeting.uuid }).then(res => res.data);
+    }
+
+    async clear_conversation(meeting: Meeting): Promise<ModelMutateResultData> {
+      return ipc_invoke(`clear_meeting_conversation`, { id: meeting.uuid }).then(res => res.data);
+    }
+
+    // Final prompt of the meeting, with the template variables replaced
+    async preview_prompt(meeting: Meeting): Promise<PromptPreview> {
+      return ipc_invoke(`preview_prompt`, { id: meeting.uuid }).then(res => res.data);
+    }
+
+    async summary_html(meeting: Meeting): Promise<string> {
+      return ipc_invoke(`render_summary_html`, { id: meeting.uuid }).then(res => res.data);
+    }
+
+    async generate_metadata(meeting: Meeting): Promise<MeetingMetadata> {
+      return ipc_invoke(`async_generate_metadata_meeting`, { id: meeting.uuid }).then(res => res.data);
+    }
+
+    async extract_action_items(meeting: Meeting): Promise<ModelMutateResultData> {
+      return ipc_invoke(`async_extract_action_items_meeting`, { id: meeting.uuid }).then(res => res.data);
+    }
+
+    async set_action_item_done(meeting: Meeting, item: ActionItem, done: boolean): Promise<ActionItem> {
+      return ipc_invoke(`set_action_item_done`, { meeting_id: meeting.uuid, item_id: item.uuid, done: done }).then(res => res.data);
+    }
+
+    async list_open_action_items(): Promise<OpenActionItem[]> {
+      return ipc_invoke(`list_open_action_items`).then(res => res.data);
+    }
+
+    async cancel_summary_stream(stream_id: string): Promise<ModelMutateResultData> {
+      return ipc_invoke(`cancel_summary_stream`, { id: stream_id }).then(res => res.data);
     }
 
     async get(id: string): Promise<Meeting> {
@@ -53,6 +146,29 @@ class MeetingModelController {
       return ipc_invoke(`export_all_meeting`, {}).then(res => res.data);
     }
 
+    async export_transcript(meeting: Meeting, format: TranscriptFormat): Promise<string> {
+      return ipc_invoke(`export_transcript_meeting`, { id: meeting.uuid, format: format }).then(res => res.data);
+    }
+
+    // Cached until a meeting of the company changes - force to generate it again
+    async summarize_company(company_id: string, force?: boolean): Promise<CompanyDigest> {
+      return ipc_invoke(`summarize_company`, { company_id: company_id, force: force }).then(res => res.data);
+    }
+
+    // Dates as YYYY-MM-DD, both included - The report is also written in the data folder
+    async generate_digest(from: string, to: string): Promise<PeriodDigest> {
+      return ipc_invoke(`generate_digest`, { from: from, to: to }).then(res => res.data);
+    }
+
+    async draft_follow_up(meeting: Meeting): Promise<FollowUpDraft> {
+      return ipc_invoke(`draft_follow_up`, { id: meeting.uuid }).then(res => res.data);
+    }
+
+    // Eml: path of the file written - Mailto: the link to open
+    async export_follow_up(meeting: Meeting, format: FollowUpFormat): Promise<string> {
+      return ipc_invoke(`export_follow_up`, { id: meeting.uuid, format: format }).then(res => res.data);
+    }
+
 }
 
 export const meetingFmc = new MeetingModelController();
@@ -70,6 +186,21 @@ class SettingModelController {
   async open_data_folder(): Promise<null> {
     return ipc_invoke(`open_data_folder`, {}).then(res => res.data);
   }
+
+  // Every prompt if names is not set - Returns the path of the file written
+  async export_prompts(format: PromptPackFormat, names?: string[]): Promise<string> {
+    return ipc_invoke(`export_prompt_pack`, { format: format, names: names }).then(res => res.data);
+  }
+
+  // A prompt with the same id or name is replaced only by a newer version
+  async import_prompts(path: string): Promise<PromptImportReport> {
+    return ipc_invoke(`import_prompt_pack`, { path: path }).then(res => res.data);
+  }
+
+  // Dates are RFC 3339 - Current month by default
+  async usage_report(from?: string, to?: string): Promise<UsageReport> {
+    return ipc_invoke(`get_usage_report`, { from: from, to: to }).then(res => res.data);
+  }
 }
 
 export const settingFmc = new SettingModelController();
@@ -102,8 +233,8 @@ class RecordingModelController {
   }
 
   async transcribe(meeting: Meeting, language: String): Promise<Meeting> { 
-    /* supported language: "En", "Fr", "Zh" */
-    return ipc_invoke(`transcribe_recording`, { path: meeting.audio_path, language: language}).then(
+    /* language: code or name from `list_transcription_languages`, "auto" to detect it */
+    return ipc_invoke(`transcribe_recording`, { path: meeting.audio_path, language: language, meeting_id: meeting.uuid}).then(
       res => {
         console.log("Transcript: " + res);
         let transcript = res.data
@@ -116,6 +247,22 @@ class RecordingModelController {
     return ipc_invoke(`get_available_audio_devices`, {}).then(res => res.data);
   }
 
+  async list_transcription_jobs(): Promise<TranscriptionJob[]> {
+    return ipc_invoke(`list_transcription_jobs`, {}).then(res => res.data);
+  }
+
+  async list_transcription_languages(): Promise<TranscriptionLanguage[]> {
+    return ipc_invoke(`list_transcription_languages`, {}).then(res => res.data);
+  }
+
+  async preview_corrections(meeting: Meeting): Promise<CorrectionPreview[]> {
+    return ipc_invoke(`preview_corrections`, { id: meeting.uuid }).then(res => res.data);
+  }
+
+  async cancel_transcription_job(id: string): Promise<TranscriptionJob> {
+    return ipc_invoke(`cancel_transcription_job`, { id: id }).then(res => res.data);
+  }
+
 }
 
 export const recorderFmc = new RecordingModelController();
@@ -1,9 +1,10 @@
-import { ActionIcon, Center, Checkbox, NativeSelect, TextInput, useComputedColorScheme } from '@mantine/core';
+import { ActionIcon, Center, Checkbox, NativeSelect, TextInput } from '@mantine/core';
 import { HeadingIcon, TrashIcon, Pencil1Icon, MagicWandIcon, ReaderIcon, UploadIcon, FileTextIcon, QuestionMarkIcon, Cross1Icon, CopyIcon } from '@radix-ui/react-icons';
 import { Stack, Group, Autocomplete, Button, Modal, Flex, Text } from '@mantine/core';
 import { useState, useEffect, useRef } from 'react';
 import { crmFmc, meetingFmc, recorderFmc, settingFmc } from '../controller';
 import { Meeting } from '../bindings';
+import { TranscriptionLanguage } from '../bindings/TranscriptionLanguage';
 import { useLocation  } from 'react-router-dom';
 import { useAppContext } from '../AppContext';
 import { useDisclosure } from '@mantine/hooks';
@@ -14,6 +15,7 @@ import StarterKit from '@tiptap/starter-kit';
 import Underline from '@tiptap/extension-underline';
 import Superscript from '@tiptap/extension-superscript';
 import SubScript from '@tiptap/extension-subscript';
+import { Markdown } from 'tiptap-markdown';
 import { invoke, window as windowTauri } from "@tauri-apps/api"
 import { TauriEvent } from "@tauri-apps/api/event"
 import { ArrowLeftIcon } from "@radix-ui/react-icons";
@@ -60,8 +62,8 @@ export default function MeetingView() {
     const [editorSelector, setEditorSelector] = useState('Summary');
     const [openedTranscriptModal, { open: openTranscriptModal, close: closeTranscriptModal }] = useDisclosure(false);
     const [openedPromptModal, { open: openPromptModal, close: closePromptModal }] = useDisclosure(false);
-    const [language, setLanguage] = useState("English");
-    const computedColorScheme = useComputedColorScheme('light', { getInitialValueInEffect: true });
+    const [language, setLanguage] = useState("en");
+    const [languages, setLanguages] = useState<TranscriptionLanguage[]>([]);
     const [checkedPublishWithPersonalNote, setCheckedPublishWithPersonalNote] = useState(false);
     const [userPrompts, _setUserPrompts] = useState<Map<string, string>>(new Map());
     const [userPromptsNameList, setUserPromptsNameList] = useState<string[]>([...userPrompts.keys()]);
@@ -81,6 +83,8 @@ export default function MeetingView() {
           Superscript,
           SubScript,
           Highlight,
+          // Summaries are stored as Markdown - Raw HTML is not rendered
+          Markdown.configure({ html: false }),
         ],
         // Init the content of the editor when a new prop is loaded
         async onCreate(props) {
@@ -89,7 +93,7 @@ export default function MeetingView() {
         },
         // triggered on every change
         onUpdate: async ({ editor }) => {
-          let content = editor.getHTML();
+          let content = editor.storage.markdown.getMarkdown();
           if (meeting) {
             meeting.summary = content;
           }
@@ -183,6 +187,7 @@ export default function MeetingView() {
             if (result.affinity_api_token != null && result.affinity_api_token.length > 0) {
                 setAffinityIntegrationEnabled(true);
             }
+            setLanguages(await recorderFmc.list_transcription_languages());
             // Fetch the meeting
             const res_meeting = await meetingFmc.get(meetingId);
             console.log(res_meeting);
@@ -247,7 +252,6 @@ export default function MeetingView() {
     async function retranscript() {
         if (!meeting) {return;}
         let clonedMeeting = { ...meeting };
-        let lang = language === "English" ? "En" : language === "Français" ? "Fr" : "Zh";
         notifications.show({
             title: 'Transcription started!',
             message: 'Watson will ping you when the transcription is done.',
@@ -260,7 +264,7 @@ export default function MeetingView() {
         await meetingFmc.increment_async_ops_count(clonedMeeting);
         try {
             updateViews(); // Update the meeting list - spiner on meeting list
-            let m = await recorderFmc.transcribe(clonedMeeting, lang);
+            let m = await recorderFmc.transcribe(clonedMeeting, language);
             let res_meeting = await meetingFmc.get(clonedMeeting.uuid);
             res_meeting.transcript = m.transcript;
             await meetingFmc.update(res_meeting); // Frontend update of transcript -> save
@@ -303,15 +307,10 @@ export default function MeetingView() {
                     <Group>
                         <Text>Notice: This action will also resummarize the meeting with the last prompt used.</Text>
                         <Text>Select language: </Text>
-                        <SegmentedControl 
-                        fullWidth 
-                        color={computedColorScheme === "light" ? 'var(--mantine-color-borealGreen-4)' : 'var(--mantine-color-borealGreen-5)'} 
-                        defaultValue={language} 
-                        onChange={(value) => {
-                            setLanguage(value)
-                        }
-                        } 
-                        data={['English', 'Français', '中文']} 
+                        <NativeSelect 
+                          value={language} 
+                          onChange={(event) => {setLanguage(event.currentTarget.value)}} 
+                          data={[{ value: 'auto', label: 'Auto detect' }, ...languages.map((lang) => ({ value: lang.code, label: lang.name }))]} 
                         />
                     </Group>
                     <Group justify="center">
@@ -1,4 +1,4 @@
-import { Group, Button, SegmentedControl, Stack, Modal, TextInput, Text, useComputedColorScheme, NativeSelect  } from '@mantine/core';
+import { Group, Button, Stack, Modal, TextInput, Text, NativeSelect  } from '@mantine/core';
 import { FilePlusIcon, PauseIcon, StopIcon, ResumeIcon, HeadingIcon, QuestionMarkIcon } from "@radix-ui/react-icons";
 import { useEffect, useState } from "react";
 import { meetingFmc, recorderFmc, sessionFmc, settingFmc } from '../controller';
@@ -13,6 +13,7 @@ import Superscript from '@tiptap/extension-superscript';
 import SubScript from '@tiptap/extension-subscript';
 import Placeholder from '@tiptap/extension-placeholder';
 import { NewMeetingNote } from '../bindings/NewMeetingNote';
+import { TranscriptionLanguage } from '../bindings/TranscriptionLanguage';
 import { invoke, window as windowTauri } from "@tauri-apps/api"
 import { TauriEvent } from "@tauri-apps/api/event"
 import { notifications } from '@mantine/notifications';
@@ -39,10 +40,10 @@ export default function Recording() {
     const { views, updateViews, notifyChangeInRecordingState } = useAppContext();
     const [recording, setRecording] = useState(false);
     const [pause, setRecordingPause] = useState(false);
-    const [language, setLanguage] = useState("English");
+    const [language, setLanguage] = useState("en");
+    const [languages, setLanguages] = useState<TranscriptionLanguage[]>([]);
     const [opened, { open, close }] = useDisclosure(false);
     const [note, setNote] = useState<NewMeetingNote | null>(null);
-    const computedColorScheme = useComputedColorScheme('light', { getInitialValueInEffect: true });
     const [promptName, setPromptName] = useState('Summarize');
     const [promptsNameList, setPromptsNameList] = useState<string[]>(['Summarize', 'Improved Hand Note']);
     const [prompts, _setPrompt] = useState<Map<string, string>>(new Map());
@@ -65,7 +66,6 @@ export default function Recording() {
        * 4. Transcribe & Summarize the meeting
        */
       info("stop recording called")
-      let lang = language == "English" ? "En" : "Fr";
       let prompt_to_use = prompts.get(promptName);
       let summarizationType = promptName;
       let user_note = "";
@@ -107,8 +107,9 @@ export default function Recording() {
           withCloseButton: true,
         });
         info("Starting transcription")
-        meeting = await recorderFmc.transcribe(meeting, lang);
-        await meetingFmc.update(meeting);
+        await recorderFmc.transcribe(meeting, language);
+        // The job saved the transcript and generated metadata, reload instead of overwriting them
+        meeting = await meetingFmc.get(meeting.uuid);
         info("Finished transcription")
         if (summarizationType == "Improved Hand Note") {
           info("improving note")
@@ -187,6 +188,7 @@ export default function Recording() {
             });
           }
           setPromptsNameList(promptsNameList); // Signal react to update the view
+          setLanguages(await recorderFmc.list_transcription_languages());
           const devices = await recorderFmc.get_available_audio_devices();
           setInputDevices(devices.input_devices.map(device => {if (device.is_default) {setSelectedInputDevice(device.name);} return device.name;}));
           setOutputDevices(devices.output_devices.map(device => {if (device.is_default) {setSelectedOutputDevice(device.name);} return device.name;}));
@@ -229,15 +231,10 @@ export default function Recording() {
 
           <Group>
             <Text>Select language: </Text>
-            <SegmentedControl 
-              fullWidth 
-              color={computedColorScheme === "light" ? 'var(--mantine-color-borealGreen-4)' : 'var(--mantine-color-borealGreen-5)'} 
-              defaultValue={language} 
-              onChange={(value) => {
-                setLanguage(value)
-              }
-              } 
-              data={['English', 'Français', '中文']} 
+            <NativeSelect 
+              value={language} 
+              onChange={(event) => {setLanguage(event.currentTarget.value)}} 
+              data={[{ value: 'auto', label: 'Auto detect' }, ...languages.map((lang) => ({ value: lang.code, label: lang.name }))]} 
             />
           </Group>
 
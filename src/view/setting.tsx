@@ -2,6 +2,7 @@ import { useDisclosure } from '@mantine/hooks';
 import { PasswordInput, Stack, Button, Textarea, NativeSelect, Fieldset, TextInput, ActionIcon, Flex, Modal, Group, Text, HoverCard, Select } from '@mantine/core';
 import { crmFmc, meetingFmc, settingFmc } from '../controller';
 import { Setting } from '../bindings/Setting';
+import { Prompt } from '../bindings/Prompt';
 import { useState, useEffect, useRef } from 'react';
 import { invoke, window as windowTauri } from "@tauri-apps/api"
 import { TauriEvent } from "@tauri-apps/api/event"
@@ -94,9 +95,11 @@ export default function Settings() {
     const [crmList, setCrmList] = useState<string | null>(null);
 
     function savePrompts() {
-      let promptList: {name: string, prompt: string}[] = [];
+      let promptList: Prompt[] = [];
       prompts.forEach((value, key) => {
-        promptList.push({name: key, prompt: value});
+        // Keep the id & version so the library can be shared - The backend bumps the version of an edited prompt
+        const known = setting?.prompts?.find((prompt) => prompt.name == key);
+        promptList.push({id: known ? known.id : null, name: key, prompt: value, version: known ? known.version : null, description: known ? known.description : null});
       });
       if (setting)
         setting.prompts = promptList;      